@@ -1,82 +1,323 @@
+use crate::schematics::shape::{Index, Region, Shape};
 
+use serde::{Deserialize, Serialize};
 
-
-
+use std::error::Error;
 use std::fmt;
 
-use std::sync::Mutex;
-
-use std::sync::atomic::{AtomicBool, Ordering};
-
-
-
-
-
-
-
-// // Error handling
+// Error handling
+//
+// Every fallible operation in Matrical returns this one error type. Variants
+// carry the values needed to explain the failure (the offending index, region
+// or shapes and the operation that raised it) instead of a bare tag, and
+// `Operation` / `Backend` wrap an underlying error so `source()` can walk the
+// chain back to the root cause.
+//
+#[derive(Debug)]
 pub enum MatricalError {
-    Regular(MatricalErrorType),
+    // The dimensions describe more elements than the storage can address
+    InvalidShape { rows: usize, cols: usize },
+    // The number of supplied values does not match the requested shape
+    LengthMismatch { expected: usize, actual: usize },
+    // Two shapes that must agree do not
+    ShapeMismatch {
+        operation: &'static str,
+        expected: Shape,
+        actual: Shape,
+    },
+    // The index is not inside the shape it was checked against
+    IndexOutOfBounds { index: Index, shape: Shape },
+    // The corners of a region are inverted, or its exclusive end overflows
+    InvalidRegion { start: Index, end: Index },
+    // Moving or sizing a region overflowed
+    RegionOverflow { origin: Index, shape: Shape },
+    // The region does not lie inside the shape it was checked against
+    RegionOutOfBounds { region: Region, shape: Shape },
+    // Two regions that must be disjoint share at least one index
+    OverlappingRegions { first: Region, second: Region },
+    // An index that must be unique was given more than once
+    DuplicateIndex { operation: &'static str, index: usize },
+    // An operation needs more values than the input provides
+    InsufficientData {
+        operation: &'static str,
+        required: usize,
+        actual: usize,
+    },
+    // A value was rejected by an operation
+    InvalidValue {
+        operation: &'static str,
+        index: Option<Index>,
+    },
+    // The context an operation depends on was rejected
+    InvalidContext { operation: &'static str },
+    // Input could not be interpreted in the expected format
+    InvalidFormat { operation: &'static str, reason: String },
+    // An operation was called without a required operand
+    MissingOperand { operation: &'static str },
+    // The operation is not supported for the given input
+    Unsupported { operation: &'static str },
+    // A Tag or TagSet would exceed one of its size bounds
+    TagLimitExceeded {
+        limit: &'static str,
+        max: usize,
+        actual: usize,
+    },
+    // A dynamically applied Gear was not given the Cog it requires
+    MissingPolicy { policy: &'static str },
+    // A lock guarding shared state was poisoned by a panicking thread
+    MutexPoisoned,
+    // An error raised while performing `operation`
+    Operation {
+        operation: &'static str,
+        source: Box<MatricalError>,
+    },
+    // A failure reported by an optional integration
+    Backend {
+        operation: &'static str,
+        source: Box<dyn Error + Send + Sync + 'static>,
+    },
     Custom(String),
-    InvalidValue,
-    InvalidContext,
-    ShouldNotOccur,
-    IndexOutOfBounds,
 }
 
-pub enum AtomicBoolError {
-    MutexPoisoned,
-    IndexOutOfBounds,
-    MissingOperand,
-}
+impl MatricalError {
+    // Wrap the error with the name of the operation that was being performed
+    pub fn during(self, operation: &'static str) -> Self {
+        MatricalError::Operation {
+            operation,
+            source: Box::new(self),
+        }
+    }
 
-pub enum MatricalErrorType {
-    IncorrectDimensions,
-    IncorrectFormat,
-}
+    // Get the innermost Matrical error, skipping `Operation` wrappers
+    pub fn root(&self) -> &MatricalError {
+        match self {
+            MatricalError::Operation { source, .. } => source.root(),
+            other => other,
+        }
+    }
 
-impl fmt::Debug for MatricalError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+    // Get the kind of this error, without its payload
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            MatricalError::InvalidShape { .. } => ErrorKind::InvalidShape,
+            MatricalError::LengthMismatch { .. } => ErrorKind::LengthMismatch,
+            MatricalError::ShapeMismatch { .. } => ErrorKind::ShapeMismatch,
+            MatricalError::IndexOutOfBounds { .. } => ErrorKind::IndexOutOfBounds,
+            MatricalError::InvalidRegion { .. } => ErrorKind::InvalidRegion,
+            MatricalError::RegionOverflow { .. } => ErrorKind::RegionOverflow,
+            MatricalError::RegionOutOfBounds { .. } => ErrorKind::RegionOutOfBounds,
+            MatricalError::OverlappingRegions { .. } => ErrorKind::OverlappingRegions,
+            MatricalError::DuplicateIndex { .. } => ErrorKind::DuplicateIndex,
+            MatricalError::InsufficientData { .. } => ErrorKind::InsufficientData,
+            MatricalError::InvalidValue { .. } => ErrorKind::InvalidValue,
+            MatricalError::InvalidContext { .. } => ErrorKind::InvalidContext,
+            MatricalError::InvalidFormat { .. } => ErrorKind::InvalidFormat,
+            MatricalError::MissingOperand { .. } => ErrorKind::MissingOperand,
+            MatricalError::Unsupported { .. } => ErrorKind::Unsupported,
+            MatricalError::TagLimitExceeded { .. } => ErrorKind::TagLimitExceeded,
+            MatricalError::MissingPolicy { .. } => ErrorKind::MissingPolicy,
+            MatricalError::MutexPoisoned => ErrorKind::MutexPoisoned,
+            MatricalError::Operation { .. } => ErrorKind::Operation,
+            MatricalError::Backend { .. } => ErrorKind::Backend,
+            MatricalError::Custom(_) => ErrorKind::Custom,
+        }
     }
 }
 
-
+// The kind of a MatricalError
+//
+// One fieldless variant per MatricalError variant, so a failure can be
+// recorded, compared and serialized without the payload it carried.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorKind {
+    InvalidShape,
+    LengthMismatch,
+    ShapeMismatch,
+    IndexOutOfBounds,
+    InvalidRegion,
+    RegionOverflow,
+    RegionOutOfBounds,
+    OverlappingRegions,
+    DuplicateIndex,
+    InsufficientData,
+    InvalidValue,
+    InvalidContext,
+    InvalidFormat,
+    MissingOperand,
+    Unsupported,
+    TagLimitExceeded,
+    MissingPolicy,
+    MutexPoisoned,
+    Operation,
+    Backend,
+    Custom,
+}
 
 impl fmt::Display for MatricalError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            MatricalError::Regular(err) => write!(f, "Regular error: {}", err.as_str()),
-            MatricalError::Custom(err) => write!(f, "Custom error: {}", err),
-            MatricalError::ShouldNotOccur => write!(f, "Other error"),
-            MatricalError::InvalidValue => write!(f, "Invalid value"),
-            MatricalError::InvalidContext => write!(f, "Invalid context"),  
-            MatricalError::IndexOutOfBounds => write!(f, "Index out of bounds"),
+            MatricalError::InvalidShape { rows, cols } => {
+                write!(f, "invalid shape {}x{}: element count overflows", rows, cols)
+            }
+            MatricalError::LengthMismatch { expected, actual } => {
+                write!(f, "expected {} values, got {}", expected, actual)
+            }
+            MatricalError::ShapeMismatch { operation, expected, actual } => write!(
+                f,
+                "{}: expected shape {}x{}, got {}x{}",
+                operation,
+                expected.rows(),
+                expected.cols(),
+                actual.rows(),
+                actual.cols()
+            ),
+            MatricalError::IndexOutOfBounds { index, shape } => write!(
+                f,
+                "index ({}, {}) out of bounds for shape {}x{}",
+                index.row(),
+                index.col(),
+                shape.rows(),
+                shape.cols()
+            ),
+            MatricalError::InvalidRegion { start, end } => write!(
+                f,
+                "invalid region with corners ({}, {}) and ({}, {})",
+                start.row(),
+                start.col(),
+                end.row(),
+                end.col()
+            ),
+            MatricalError::RegionOverflow { origin, shape } => write!(
+                f,
+                "region of shape {}x{} at ({}, {}) overflows",
+                shape.rows(),
+                shape.cols(),
+                origin.row(),
+                origin.col()
+            ),
+            MatricalError::RegionOutOfBounds { region, shape } => write!(
+                f,
+                "region ({}, {})..({}, {}) out of bounds for shape {}x{}",
+                region.start().row(),
+                region.start().col(),
+                region.end().row(),
+                region.end().col(),
+                shape.rows(),
+                shape.cols()
+            ),
+            MatricalError::OverlappingRegions { first, second } => write!(
+                f,
+                "regions ({}, {})..({}, {}) and ({}, {})..({}, {}) overlap",
+                first.start().row(),
+                first.start().col(),
+                first.end().row(),
+                first.end().col(),
+                second.start().row(),
+                second.start().col(),
+                second.end().row(),
+                second.end().col()
+            ),
+            MatricalError::DuplicateIndex { operation, index } => {
+                write!(f, "{}: index {} given more than once", operation, index)
+            }
+            MatricalError::InsufficientData { operation, required, actual } => {
+                write!(f, "{}: needs at least {} values, got {}", operation, required, actual)
+            }
+            MatricalError::InvalidValue { operation, index: Some(index) } => {
+                write!(f, "{}: invalid value at ({}, {})", operation, index.row(), index.col())
+            }
+            MatricalError::InvalidValue { operation, index: None } => {
+                write!(f, "{}: invalid value", operation)
+            }
+            MatricalError::InvalidContext { operation } => write!(f, "{}: invalid context", operation),
+            MatricalError::InvalidFormat { operation, reason } => {
+                write!(f, "{}: invalid format: {}", operation, reason)
+            }
+            MatricalError::MissingOperand { operation } => write!(f, "{}: missing operand", operation),
+            MatricalError::Unsupported { operation } => write!(f, "{}: unsupported operation", operation),
+            MatricalError::TagLimitExceeded { limit, max, actual } => {
+                write!(f, "tag {} {} exceeds the maximum of {}", limit, actual, max)
+            }
+            MatricalError::MissingPolicy { policy } => write!(f, "missing policy {}", policy),
+            MatricalError::MutexPoisoned => write!(f, "mutex poisoned"),
+            MatricalError::Operation { operation, .. } => write!(f, "{} failed", operation),
+            MatricalError::Backend { operation, .. } => write!(f, "{}: backend failure", operation),
+            MatricalError::Custom(err) => write!(f, "{}", err),
         }
     }
 }
 
-impl MatricalErrorType {
-    fn as_str(&self) -> &str {
-        match *self {
-            MatricalErrorType::IncorrectDimensions => "IncorrectDimensions",
-            MatricalErrorType::IncorrectFormat => "IncorrectFormat",
+impl Error for MatricalError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MatricalError::Operation { source, .. } => Some(source.as_ref()),
+            MatricalError::Backend { source, .. } => Some(source.as_ref()),
+            _ => None,
         }
     }
 }
 
-// ERROR
-
-#[derive(Debug)]
-pub struct Error {
-    message: String,
-    // you can add more fields here
+impl<T> From<std::sync::PoisonError<T>> for MatricalError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        MatricalError::MutexPoisoned
+    }
 }
 
-// impl Error {
-//     pub fn new(message: &str) -> Self {
-//         Error {
-//             message: message.to_string(),
-//         }
-//     }
-// }
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_does_not_recurse() {
+        let err = MatricalError::Custom("boom".to_string());
+        assert_eq!(format!("{:?}", err), "Custom(\"boom\")");
+    }
+
+    #[test]
+    fn test_display_carries_context() {
+        let err = MatricalError::IndexOutOfBounds {
+            index: Index::new(3, 1),
+            shape: Shape::new(2, 2).unwrap(),
+        };
+        assert_eq!(err.to_string(), "index (3, 1) out of bounds for shape 2x2");
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let inner = MatricalError::Unsupported { operation: "invert" };
+        let err = inner.during("pipeline");
+
+        assert_eq!(err.to_string(), "pipeline failed");
+        let source = err.source().unwrap();
+        assert_eq!(source.to_string(), "invert: unsupported operation");
+        assert!(source.source().is_none());
+        assert!(matches!(err.root(), MatricalError::Unsupported { operation: "invert" }));
+        assert_eq!(err.kind(), ErrorKind::Operation);
+        assert_eq!(err.root().kind(), ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn test_backend_source() {
+        let io = std::io::Error::other("disk full");
+        let err = MatricalError::Backend {
+            operation: "persist",
+            source: Box::new(io),
+        };
+        assert_eq!(err.source().unwrap().to_string(), "disk full");
+    }
+
+    #[test]
+    fn test_poison_converts() {
+        let lock = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let clone = lock.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = clone.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+
+        let err: MatricalError = lock.lock().unwrap_err().into();
+        assert!(matches!(err, MatricalError::MutexPoisoned));
+    }
+}
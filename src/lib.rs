@@ -12,30 +12,39 @@ use std::fmt;
 use std::marker::PhantomData;
 use std::ops::Range;
 use std::sync::{Arc, Mutex};
-use std::error::Error;
 
 use crossbeam::atomic::AtomicCell;
 use crossbeam::queue::SegQueue;
 use dashmap::DashMap as HashMap;
 
-mod error;
-
-use error::{AtomicBoolError, MatricalError, MatricalErrorType};
+pub mod error;
+pub use error::{ErrorKind, MatricalError};
 
 pub mod operations;
 pub use operations::*;
 pub use operations::mechanics::*;
 
 pub mod strategies;
+pub use strategies::baseline::*;
 pub use strategies::cog::*;
+pub use strategies::gather::*;
 pub use strategies::gear::*;
 pub use strategies::lens::*;
+pub use strategies::pipeline::*;
+pub use strategies::report::*;
+pub use strategies::rolling::*;
+pub use strategies::selector::*;
+pub use strategies::statistics::*;
 pub use strategies::tag::*;
+pub use strategies::validity::*;
 
 pub mod schematics;
 pub use schematics::data::*;
 pub use schematics::element::*;
+pub use schematics::mask::*;
+pub use schematics::masked::*;
 pub use schematics::matrix::*;
+pub use schematics::shape::*;
 pub use schematics::vector::*;
 
 
@@ -1,10 +1,492 @@
+use crate::error::MatricalError;
+use crate::schematics::masked::MaskedMatrix;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Axis, Index};
+use crate::strategies::lens::Lens;
+use crate::strategies::statistics::{cast, mean, quantile};
+use crate::strategies::validity::MaskedLens;
 
+use num_traits::Float;
 
+// Reductions
+//
+// A Reducer collapses a set of values into one, over a whole Lens or lane by
+// lane along an `Axis`: `Axis::Column` yields one value per column, `Axis::Row`
+// one per row. A reduction that has too few values to be defined, such as the
+// mean of an empty lane, yields NaN. Reductions over a MaskedLens only see the
+// valid cells, so a missing measurement never counts as zero. Sums and means
+// are compensated and variances use Welford's update, so reducing long
+// histories does not drift the way a naive running sum does.
+//
 
-// use crate::*;
+// A statistic collapsing a set of values into one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reducer<V> {
+    Mean,
+    Sum,
+    Min,
+    Max,
+    Variance { ddof: usize },
+    Std { ddof: usize },
+    Median,
+    // The q-th quantile, 0 <= q <= 1, interpolated linearly
+    Quantile(V),
+    // The number of values reduced, which for a MaskedLens is the number of valid cells
+    Count,
+}
 
-// // Defines a set of methods that can be used to aggregate a given data set.
-// //
-// pub trait AggregateStrategy: Strategy {
-//     fn aggregate(&self, data: &HashMap<String, String>) -> Result<(), String>;
-// }
\ No newline at end of file
+impl<V: Float> Reducer<V> {
+    // Reduce a set of values, returning None when there are too few for the statistic
+    pub fn reduce(&self, values: &[V]) -> Option<V> {
+        if let Reducer::Count = self {
+            return Some(cast(values.len()));
+        }
+        if values.is_empty() {
+            return None;
+        }
+        let has_nan = values.iter().any(|value| value.is_nan());
+        let value = match *self {
+            Reducer::Mean => mean(values),
+            Reducer::Sum => values.iter().copied().collect::<KahanSum<V>>().sum(),
+            Reducer::Min if has_nan => V::nan(),
+            Reducer::Min => values.iter().copied().fold(V::infinity(), V::min),
+            Reducer::Max if has_nan => V::nan(),
+            Reducer::Max => values.iter().copied().fold(V::neg_infinity(), V::max),
+            Reducer::Variance { ddof } | Reducer::Std { ddof } if values.len() <= ddof => return None,
+            Reducer::Variance { ddof } => values.iter().copied().collect::<Welford<V>>().variance(ddof),
+            Reducer::Std { ddof } => values.iter().copied().collect::<Welford<V>>().std(ddof),
+            Reducer::Median => quantile(values, V::from(0.5).unwrap_or_else(V::nan)),
+            Reducer::Quantile(q) => quantile(values, q),
+            Reducer::Count => cast(values.len()),
+        };
+        Some(value)
+    }
+
+    // Reject parameters the statistic is not defined for
+    pub(crate) fn check(&self, operation: &'static str) -> Result<(), MatricalError> {
+        match *self {
+            Reducer::Quantile(q) if !(q >= V::zero() && q <= V::one()) => {
+                Err(MatricalError::InvalidValue { operation, index: None })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// Struct accumulating a compensated sum
+//
+// Neumaier's variant of Kahan summation: the low-order bits lost by each
+// addition are carried in a separate compensation term, so the error of the
+// sum stays bounded instead of growing with the number of values. Once the
+// running sum is infinite or NaN compensation is meaningless and is dropped.
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KahanSum<V> {
+    sum: V,
+    compensation: V,
+}
+
+impl<V: Float> KahanSum<V> {
+    // Create a new, empty KahanSum
+    pub fn new() -> Self {
+        Self {
+            sum: V::zero(),
+            compensation: V::zero(),
+        }
+    }
+
+    // Add a value to the sum
+    pub fn add(&mut self, value: V) {
+        let total = self.sum + value;
+        if total.is_finite() {
+            if self.sum.abs() >= value.abs() {
+                self.compensation = self.compensation + ((self.sum - total) + value);
+            } else {
+                self.compensation = self.compensation + ((value - total) + self.sum);
+            }
+        }
+        self.sum = total;
+    }
+
+    // Get the compensated sum
+    pub fn sum(&self) -> V {
+        if self.sum.is_finite() {
+            self.sum + self.compensation
+        } else {
+            self.sum
+        }
+    }
+}
+
+impl<V: Float> Default for KahanSum<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Float> FromIterator<V> for KahanSum<V> {
+    fn from_iter<I: IntoIterator<Item = V>>(values: I) -> Self {
+        let mut sum = Self::new();
+        values.into_iter().for_each(|value| sum.add(value));
+        sum
+    }
+}
+
+// Struct accumulating a running mean and variance
+//
+// Welford's online update keeps the mean and the sum of squared deviations
+// from it, so the variance is computed in one pass without the cancellation
+// of subtracting two large sums of squares.
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Welford<V> {
+    count: usize,
+    mean: V,
+    squares: V,
+}
+
+impl<V: Float> Welford<V> {
+    // Create a new, empty Welford accumulator
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            mean: V::zero(),
+            squares: V::zero(),
+        }
+    }
+
+    // Add a value
+    pub fn add(&mut self, value: V) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean = self.mean + delta / cast(self.count);
+        self.squares = self.squares + delta * (value - self.mean);
+    }
+
+    // Get the number of values added
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Get the mean, or NaN when no values were added
+    pub fn mean(&self) -> V {
+        if self.count == 0 {
+            V::nan()
+        } else {
+            self.mean
+        }
+    }
+
+    // Get the variance with `ddof` delta degrees of freedom
+    pub fn variance(&self, ddof: usize) -> V {
+        self.squares / cast(self.count.saturating_sub(ddof))
+    }
+
+    // Get the standard deviation with `ddof` delta degrees of freedom
+    pub fn std(&self, ddof: usize) -> V {
+        self.variance(ddof).sqrt()
+    }
+}
+
+impl<V: Float> Default for Welford<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Float> FromIterator<V> for Welford<V> {
+    fn from_iter<I: IntoIterator<Item = V>>(values: I) -> Self {
+        let mut welford = Self::new();
+        values.into_iter().for_each(|value| welford.add(value));
+        welford
+    }
+}
+
+// The Lens-relative index of `position` within lane `lane` along `axis`
+fn lane_index(axis: Axis, lane: usize, position: usize) -> Index {
+    match axis {
+        Axis::Column => Index::new(position, lane),
+        Axis::Row => Index::new(lane, position),
+    }
+}
+
+// Reduce the cells of `lens` for which `keep` holds
+fn reduce_all<V, F>(lens: &Lens<'_, V>, reducer: Reducer<V>, keep: F) -> Result<V, MatricalError>
+where
+    V: Float,
+    F: Fn(Index) -> bool,
+{
+    reducer.check("reduce")?;
+    let values: Vec<V> = lens.indexed_iter().filter(|(index, _)| keep(*index)).map(|(_, value)| *value).collect();
+    Ok(reducer.reduce(&values).unwrap_or_else(V::nan))
+}
+
+// Reduce each lane of `lens` along `axis`, over the cells for which `keep` holds
+fn reduce_lanes<V, F>(lens: &Lens<'_, V>, axis: Axis, reducer: Reducer<V>, keep: F) -> Result<Vec<V>, MatricalError>
+where
+    V: Float,
+    F: Fn(Index) -> bool,
+{
+    reducer.check("reduce")?;
+    let view = lens.view();
+    let mut values = Vec::new();
+    let reduced = view
+        .lanes(axis.lane_axis())
+        .into_iter()
+        .enumerate()
+        .map(|(lane, cells)| {
+            values.clear();
+            for (position, value) in cells.iter().enumerate() {
+                if keep(lane_index(axis, lane, position)) {
+                    values.push(*value);
+                }
+            }
+            reducer.reduce(&values).unwrap_or_else(V::nan)
+        })
+        .collect();
+    Ok(reduced)
+}
+
+// Find the cell holding the value `better` prefers over every other, the first
+// on ties, or None when no cell is kept or a kept cell is NaN
+fn arg_extreme<'v, V, I, F>(cells: I, better: F) -> Option<Index>
+where
+    V: Float + 'v,
+    I: Iterator<Item = (Index, &'v V)>,
+    F: Fn(V, V) -> bool,
+{
+    let mut best: Option<(Index, V)> = None;
+    for (index, &value) in cells {
+        if value.is_nan() {
+            return None;
+        }
+        match best {
+            Some((_, current)) if !better(value, current) => {}
+            _ => best = Some((index, value)),
+        }
+    }
+    best.map(|(index, _)| index)
+}
+
+// Find the extreme of each lane of `lens` along `axis`, as a position within the lane
+fn arg_extreme_lanes<V, K, F>(lens: &Lens<'_, V>, axis: Axis, keep: K, better: F) -> Vec<Option<usize>>
+where
+    V: Float,
+    K: Fn(Index) -> bool,
+    F: Fn(V, V) -> bool + Copy,
+{
+    let view = lens.view();
+    view.lanes(axis.lane_axis())
+        .into_iter()
+        .enumerate()
+        .map(|(lane, cells)| {
+            let cells = cells
+                .into_iter()
+                .enumerate()
+                .map(|(position, value)| (lane_index(axis, lane, position), value))
+                .filter(|(index, _)| keep(*index));
+            let index = arg_extreme(cells, better)?;
+            Some(match axis {
+                Axis::Column => index.row(),
+                Axis::Row => index.col(),
+            })
+        })
+        .collect()
+}
+
+impl<V: Float> Lens<'_, V> {
+    // Reduce every cell to one value
+    pub fn reduce(&self, reducer: Reducer<V>) -> Result<V, MatricalError> {
+        reduce_all(self, reducer, |_| true)
+    }
+
+    // Reduce each lane along `axis` to one value
+    pub fn reduce_along(&self, axis: Axis, reducer: Reducer<V>) -> Result<Vec<V>, MatricalError> {
+        reduce_lanes(self, axis, reducer, |_| true)
+    }
+
+    // Find the first cell holding the smallest value, or None if the Lens is empty or holds NaN
+    pub fn argmin(&self) -> Option<Index> {
+        arg_extreme(self.indexed_iter(), |value, best| value < best)
+    }
+
+    // Find the first cell holding the largest value, or None if the Lens is empty or holds NaN
+    pub fn argmax(&self) -> Option<Index> {
+        arg_extreme(self.indexed_iter(), |value, best| value > best)
+    }
+
+    // Find the position of the smallest value in each lane along `axis`
+    pub fn argmin_along(&self, axis: Axis) -> Vec<Option<usize>> {
+        arg_extreme_lanes(self, axis, |_| true, |value, best| value < best)
+    }
+
+    // Find the position of the largest value in each lane along `axis`
+    pub fn argmax_along(&self, axis: Axis) -> Vec<Option<usize>> {
+        arg_extreme_lanes(self, axis, |_| true, |value, best| value > best)
+    }
+}
+
+impl<V: Float> Matrix<V> {
+    // Reduce every cell to one value
+    pub fn reduce(&self, reducer: Reducer<V>) -> Result<V, MatricalError> {
+        self.as_lens().reduce(reducer)
+    }
+
+    // Reduce each lane along `axis` to one value
+    pub fn reduce_along(&self, axis: Axis, reducer: Reducer<V>) -> Result<Vec<V>, MatricalError> {
+        self.as_lens().reduce_along(axis, reducer)
+    }
+
+    // Find the first cell holding the smallest value, or None if the Matrix is empty or holds NaN
+    pub fn argmin(&self) -> Option<Index> {
+        self.as_lens().argmin()
+    }
+
+    // Find the first cell holding the largest value, or None if the Matrix is empty or holds NaN
+    pub fn argmax(&self) -> Option<Index> {
+        self.as_lens().argmax()
+    }
+
+    // Find the position of the smallest value in each lane along `axis`
+    pub fn argmin_along(&self, axis: Axis) -> Vec<Option<usize>> {
+        self.as_lens().argmin_along(axis)
+    }
+
+    // Find the position of the largest value in each lane along `axis`
+    pub fn argmax_along(&self, axis: Axis) -> Vec<Option<usize>> {
+        self.as_lens().argmax_along(axis)
+    }
+}
+
+impl<V: Float> MaskedLens<'_, V> {
+    // Reduce every valid cell to one value
+    pub fn reduce(&self, reducer: Reducer<V>) -> Result<V, MatricalError> {
+        reduce_all(self.lens(), reducer, |index| self.is_valid(index).unwrap_or(false))
+    }
+
+    // Reduce the valid cells of each lane along `axis` to one value
+    pub fn reduce_along(&self, axis: Axis, reducer: Reducer<V>) -> Result<Vec<V>, MatricalError> {
+        reduce_lanes(self.lens(), axis, reducer, |index| self.is_valid(index).unwrap_or(false))
+    }
+
+    // Find the first valid cell holding the smallest value
+    pub fn argmin(&self) -> Option<Index> {
+        let cells = self.lens().indexed_iter().filter(|(index, _)| self.is_valid(*index).unwrap_or(false));
+        arg_extreme(cells, |value, best| value < best)
+    }
+
+    // Find the first valid cell holding the largest value
+    pub fn argmax(&self) -> Option<Index> {
+        let cells = self.lens().indexed_iter().filter(|(index, _)| self.is_valid(*index).unwrap_or(false));
+        arg_extreme(cells, |value, best| value > best)
+    }
+
+    // Find the position of the smallest valid value in each lane along `axis`
+    pub fn argmin_along(&self, axis: Axis) -> Vec<Option<usize>> {
+        arg_extreme_lanes(self.lens(), axis, |index| self.is_valid(index).unwrap_or(false), |value, best| value < best)
+    }
+
+    // Find the position of the largest valid value in each lane along `axis`
+    pub fn argmax_along(&self, axis: Axis) -> Vec<Option<usize>> {
+        arg_extreme_lanes(self.lens(), axis, |index| self.is_valid(index).unwrap_or(false), |value, best| value > best)
+    }
+
+    // Count the valid cells in each lane along `axis`
+    pub fn count_valid_along(&self, axis: Axis) -> Vec<usize> {
+        let (lanes, len) = match axis {
+            Axis::Column => (self.shape().cols(), self.shape().rows()),
+            Axis::Row => (self.shape().rows(), self.shape().cols()),
+        };
+        (0..lanes)
+            .map(|lane| {
+                (0..len)
+                    .filter(|&position| self.is_valid(lane_index(axis, lane, position)).unwrap_or(false))
+                    .count()
+            })
+            .collect()
+    }
+}
+
+impl<V: Float> MaskedMatrix<V> {
+    // Reduce every valid cell to one value
+    pub fn reduce(&self, reducer: Reducer<V>) -> Result<V, MatricalError> {
+        self.as_lens().reduce(reducer)
+    }
+
+    // Reduce the valid cells of each lane along `axis` to one value
+    pub fn reduce_along(&self, axis: Axis, reducer: Reducer<V>) -> Result<Vec<V>, MatricalError> {
+        self.as_lens().reduce_along(axis, reducer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::shape::Region;
+
+    fn matrix() -> Matrix<f64> {
+        Matrix::from_shape_vec((3, 3), vec![4.0, 1.0, 7.0, 2.0, 9.0, 7.0, 6.0, 5.0, 0.0]).unwrap()
+    }
+
+    #[test]
+    fn test_whole_reductions() {
+        let matrix = matrix();
+        assert_eq!(matrix.reduce(Reducer::Sum).unwrap(), 41.0);
+        assert_eq!(matrix.reduce(Reducer::Min).unwrap(), 0.0);
+        assert_eq!(matrix.reduce(Reducer::Max).unwrap(), 9.0);
+        assert_eq!(matrix.reduce(Reducer::Median).unwrap(), 5.0);
+        assert_eq!(matrix.reduce(Reducer::Count).unwrap(), 9.0);
+        assert_eq!(matrix.argmin(), Some(Index::new(2, 2)));
+        // Ties resolve to the first position in the lane
+        assert_eq!(matrix.argmax_along(Axis::Column), vec![Some(2), Some(1), Some(0)]);
+        assert!(matches!(
+            matrix.reduce(Reducer::Quantile(-0.5)),
+            Err(MatricalError::InvalidValue { operation: "reduce", .. })
+        ));
+    }
+
+    #[test]
+    fn test_reductions_along_axes() {
+        let matrix = matrix();
+        assert_eq!(matrix.reduce_along(Axis::Column, Reducer::Sum).unwrap(), vec![12.0, 15.0, 14.0]);
+        assert_eq!(matrix.reduce_along(Axis::Row, Reducer::Mean).unwrap(), vec![4.0, 6.0, 11.0 / 3.0]);
+        assert_eq!(matrix.reduce_along(Axis::Column, Reducer::Variance { ddof: 1 }).unwrap()[0], 4.0);
+        assert_eq!(matrix.reduce_along(Axis::Column, Reducer::Std { ddof: 1 }).unwrap()[0], 2.0);
+        assert_eq!(matrix.argmin_along(Axis::Column), vec![Some(1), Some(0), Some(2)]);
+
+        let lens = matrix.lens(Region::new((0, 0).into(), (1, 3).into()).unwrap()).unwrap();
+        let variances = lens.reduce_along(Axis::Column, Reducer::Variance { ddof: 1 }).unwrap();
+        assert!(variances.iter().all(|variance| variance.is_nan()));
+    }
+
+    #[test]
+    fn test_masked_reductions_skip_invalid_cells() {
+        let masked = MaskedMatrix::from_options((2, 2), vec![Some(1.0), None, Some(3.0), Some(f64::NAN)]).unwrap();
+        assert!(masked.reduce(Reducer::Sum).unwrap().is_nan());
+        let lens = masked.as_lens();
+        assert_eq!(lens.count_valid_along(Axis::Column), vec![2, 1]);
+        assert_eq!(lens.count_valid_along(Axis::Row), vec![1, 2]);
+        assert_eq!(masked.reduce_along(Axis::Row, Reducer::Count).unwrap(), vec![1.0, 2.0]);
+
+        let mut masked = masked;
+        masked.invalidate((1, 1)).unwrap();
+        assert_eq!(masked.reduce(Reducer::Sum).unwrap(), 4.0);
+        assert_eq!(masked.as_lens().argmax(), Some(Index::new(1, 0)));
+        assert_eq!(masked.as_lens().argmin_along(Axis::Column), vec![Some(0), None]);
+        assert!(masked.reduce_along(Axis::Column, Reducer::Mean).unwrap()[1].is_nan());
+    }
+
+    #[test]
+    fn test_compensated_accumulators() {
+        let sum: KahanSum<f64> = [1e16, 1.0, -1e16].into_iter().collect();
+        assert_eq!(sum.sum(), 1.0);
+        assert_eq!(std::iter::repeat_n(0.1, 1_000_000).collect::<KahanSum<f64>>().sum(), 100_000.0);
+        assert_eq!([1.0, f64::INFINITY].into_iter().collect::<KahanSum<f64>>().sum(), f64::INFINITY);
+
+        let welford: Welford<f64> = [4.0, 7.0, 13.0, 16.0].into_iter().map(|value| value + 1e9).collect();
+        assert_eq!(welford.count(), 4);
+        assert_eq!(welford.mean(), 1e9 + 10.0);
+        assert_eq!(welford.variance(1), 30.0);
+        assert!(Welford::<f64>::new().mean().is_nan());
+    }
+}
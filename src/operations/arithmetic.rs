@@ -0,0 +1,292 @@
+use crate::error::MatricalError;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::Shape;
+use crate::strategies::lens::{Lens, LensMut};
+
+use ndarray::Array2;
+use num_traits::Float;
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// Element-wise arithmetic
+//
+// Binary operations between Matrices and Lenses broadcast: two shapes are
+// compatible when, in each dimension, they are equal or one of them is 1, so
+// a 1 x n row vector applies to every row and an m x 1 column vector to every
+// column. The result takes the larger extent in each dimension. Incompatible
+// shapes are a `ShapeMismatch` error, which is why the `std::ops` impls
+// between Matrices and Lenses output a `Result`; operations with a scalar
+// cannot fail and output the Matrix directly. The in-place `try_*_assign`
+// methods on LensMut never change the shape of their target, so only the
+// right-hand side is broadcast.
+//
+
+// Get the shape `lhs` and `rhs` broadcast to
+pub fn broadcast_shape(lhs: Shape, rhs: Shape, operation: &'static str) -> Result<Shape, MatricalError> {
+    let extent = |lhs: usize, rhs: usize| match (lhs, rhs) {
+        (lhs, rhs) if lhs == rhs || rhs == 1 => Some(lhs),
+        (1, rhs) => Some(rhs),
+        _ => None,
+    };
+    match (extent(lhs.rows(), rhs.rows()), extent(lhs.cols(), rhs.cols())) {
+        (Some(rows), Some(cols)) => Shape::new(rows, cols),
+        _ => Err(MatricalError::ShapeMismatch {
+            operation,
+            expected: lhs,
+            actual: rhs,
+        }),
+    }
+}
+
+// The cell of an operand of extent `dim` read for output cell (row, col)
+pub(crate) fn broadcast_index(dim: (usize, usize), row: usize, col: usize) -> (usize, usize) {
+    (if dim.0 == 1 { 0 } else { row }, if dim.1 == 1 { 0 } else { col })
+}
+
+// Combine two operands cell by cell after broadcasting them to a common shape
+pub(crate) fn zip_with<A, B, C, F>(lhs: &Lens<'_, A>, rhs: &Lens<'_, B>, operation: &'static str, f: F) -> Result<Matrix<C>, MatricalError>
+where
+    F: Fn(&A, &B) -> C,
+{
+    let shape = broadcast_shape(lhs.shape(), rhs.shape(), operation)?;
+    let (lhs, rhs) = (lhs.view(), rhs.view());
+    let data = Array2::from_shape_fn((shape.rows(), shape.cols()), |(row, col)| {
+        f(&lhs[broadcast_index(lhs.dim(), row, col)], &rhs[broadcast_index(rhs.dim(), row, col)])
+    });
+    Ok(Matrix::from_array(data))
+}
+
+// Combine `rhs` into `target` in place, broadcasting `rhs` to the target shape
+pub(crate) fn zip_assign<V, B, F>(target: &mut LensMut<'_, V>, rhs: &Lens<'_, B>, operation: &'static str, f: F) -> Result<(), MatricalError>
+where
+    F: Fn(&mut V, &B),
+{
+    let shape = target.shape();
+    if broadcast_shape(shape, rhs.shape(), operation)? != shape {
+        return Err(MatricalError::ShapeMismatch {
+            operation,
+            expected: shape,
+            actual: rhs.shape(),
+        });
+    }
+    let rhs = rhs.view();
+    for ((row, col), value) in target.view_mut().indexed_iter_mut() {
+        f(value, &rhs[broadcast_index(rhs.dim(), row, col)]);
+    }
+    Ok(())
+}
+
+// Implement a broadcasting binary operator; callers bring `Lens`, `LensMut`,
+// `Matrix`, `MatricalError`, `zip_with` and `zip_assign` into scope
+macro_rules! binary_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $try_assign:ident, $name:literal) => {
+        impl<V: Clone + $op<Output = V>> $op<&Lens<'_, V>> for &Lens<'_, V> {
+            type Output = Result<Matrix<V>, MatricalError>;
+
+            fn $method(self, rhs: &Lens<'_, V>) -> Self::Output {
+                zip_with(self, rhs, $name, |lhs, rhs| lhs.clone().$method(rhs.clone()))
+            }
+        }
+
+        impl<V: Clone + $op<Output = V>> $op<&Lens<'_, V>> for &Matrix<V> {
+            type Output = Result<Matrix<V>, MatricalError>;
+
+            fn $method(self, rhs: &Lens<'_, V>) -> Self::Output {
+                zip_with(&self.as_lens(), rhs, $name, |lhs, rhs| lhs.clone().$method(rhs.clone()))
+            }
+        }
+
+        impl<V: Clone + $op<Output = V>> $op<&Matrix<V>> for &Lens<'_, V> {
+            type Output = Result<Matrix<V>, MatricalError>;
+
+            fn $method(self, rhs: &Matrix<V>) -> Self::Output {
+                zip_with(self, &rhs.as_lens(), $name, |lhs, rhs| lhs.clone().$method(rhs.clone()))
+            }
+        }
+
+        impl<V: Clone + $op<Output = V>> $op<&Matrix<V>> for &Matrix<V> {
+            type Output = Result<Matrix<V>, MatricalError>;
+
+            fn $method(self, rhs: &Matrix<V>) -> Self::Output {
+                zip_with(&self.as_lens(), &rhs.as_lens(), $name, |lhs, rhs| lhs.clone().$method(rhs.clone()))
+            }
+        }
+
+        impl<V: Clone + $op<Output = V>> $op<V> for &Lens<'_, V> {
+            type Output = Matrix<V>;
+
+            fn $method(self, rhs: V) -> Matrix<V> {
+                Matrix::from_array(self.view().map(|lhs| lhs.clone().$method(rhs.clone())))
+            }
+        }
+
+        impl<V: Clone + $op<Output = V>> $op<V> for &Matrix<V> {
+            type Output = Matrix<V>;
+
+            fn $method(self, rhs: V) -> Matrix<V> {
+                $op::$method(&self.as_lens(), rhs)
+            }
+        }
+
+        impl<V: Clone + $op_assign> $op_assign<V> for LensMut<'_, V> {
+            fn $method_assign(&mut self, rhs: V) {
+                self.map_inplace(|value| value.$method_assign(rhs.clone()));
+            }
+        }
+
+        impl<V> LensMut<'_, V> {
+            // Apply the operation in place, broadcasting `rhs` to the shape of the LensMut
+            pub fn $try_assign(&mut self, rhs: &Lens<'_, V>) -> Result<(), MatricalError>
+            where
+                V: Clone + $op_assign,
+            {
+                zip_assign(self, rhs, $name, |value, rhs| value.$method_assign(rhs.clone()))
+            }
+        }
+    };
+}
+
+pub(crate) use binary_op;
+
+binary_op!(Add, add, AddAssign, add_assign, try_add_assign, "add");
+binary_op!(Sub, sub, SubAssign, sub_assign, try_sub_assign, "sub");
+binary_op!(Mul, mul, MulAssign, mul_assign, try_mul_assign, "mul");
+binary_op!(Div, div, DivAssign, div_assign, try_div_assign, "div");
+
+impl<V: Clone + Neg<Output = V>> Neg for &Lens<'_, V> {
+    type Output = Matrix<V>;
+
+    fn neg(self) -> Matrix<V> {
+        Matrix::from_array(self.view().map(|value| -value.clone()))
+    }
+}
+
+impl<V: Clone + Neg<Output = V>> Neg for &Matrix<V> {
+    type Output = Matrix<V>;
+
+    fn neg(self) -> Matrix<V> {
+        -&self.as_lens()
+    }
+}
+
+impl<V: Float> Lens<'_, V> {
+    // Raise every value to the power `exponent`
+    pub fn powf(&self, exponent: V) -> Matrix<V> {
+        Matrix::from_array(self.view().map(|value| value.powf(exponent)))
+    }
+
+    // Raise every value to the integer power `exponent`
+    pub fn powi(&self, exponent: i32) -> Matrix<V> {
+        Matrix::from_array(self.view().map(|value| value.powi(exponent)))
+    }
+
+    // Raise every value to the power of the matching, broadcast value of `exponents`
+    pub fn pow(&self, exponents: &Lens<'_, V>) -> Result<Matrix<V>, MatricalError> {
+        zip_with(self, exponents, "pow", |value, exponent| value.powf(*exponent))
+    }
+}
+
+impl<V: Float> Matrix<V> {
+    // Raise every value to the power `exponent`
+    pub fn powf(&self, exponent: V) -> Matrix<V> {
+        self.as_lens().powf(exponent)
+    }
+
+    // Raise every value to the integer power `exponent`
+    pub fn powi(&self, exponent: i32) -> Matrix<V> {
+        self.as_lens().powi(exponent)
+    }
+
+    // Raise every value to the power of the matching, broadcast value of `exponents`
+    pub fn pow(&self, exponents: &Lens<'_, V>) -> Result<Matrix<V>, MatricalError> {
+        self.as_lens().pow(exponents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::shape::Region;
+
+    fn matrix() -> Matrix<f64> {
+        Matrix::from_fn((2, 3), |row, col| (row * 3 + col) as f64).unwrap()
+    }
+
+    fn values(matrix: &Matrix<f64>) -> Vec<f64> {
+        matrix.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_same_shape() {
+        let a = matrix();
+        let b = Matrix::from_fn((2, 3), |_, _| 2.0).unwrap();
+        assert_eq!(values(&(&a + &b).unwrap()), vec![2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+        assert_eq!(values(&(&a - &b).unwrap()), vec![-2.0, -1.0, 0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(values(&(&a * &b).unwrap()), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(values(&(&a / &b).unwrap()), vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5]);
+    }
+
+    #[test]
+    fn test_broadcast_row_and_column_vectors() {
+        let a = matrix();
+        let row = Matrix::from_shape_vec((1, 3), vec![10.0, 20.0, 30.0]).unwrap();
+        let col = Matrix::from_shape_vec((2, 1), vec![100.0, 200.0]).unwrap();
+        assert_eq!(values(&(&a + &row).unwrap()), vec![10.0, 21.0, 32.0, 13.0, 24.0, 35.0]);
+        assert_eq!(values(&(&a + &col).unwrap()), vec![100.0, 101.0, 102.0, 203.0, 204.0, 205.0]);
+
+        // An outer sum of a column and a row vector
+        let outer = (&col + &row).unwrap();
+        assert_eq!(outer.shape(), Shape::new(2, 3).unwrap());
+        assert_eq!(values(&outer), vec![110.0, 120.0, 130.0, 210.0, 220.0, 230.0]);
+    }
+
+    #[test]
+    fn test_shape_mismatch_is_an_error() {
+        let a = matrix();
+        let b: Matrix<f64> = Matrix::zeros((3, 2)).unwrap();
+        assert!(matches!(
+            &a + &b,
+            Err(MatricalError::ShapeMismatch { operation: "add", .. })
+        ));
+        assert!(broadcast_shape(a.shape(), Shape::new(2, 2).unwrap(), "mul").is_err());
+    }
+
+    #[test]
+    fn test_lenses_and_scalars() {
+        let a = matrix();
+        let lens = a.as_lens();
+        let left = lens.lens(Region::new((0, 0).into(), (2, 2).into()).unwrap()).unwrap();
+        let last_col = lens.col(2).unwrap();
+        assert_eq!(values(&(&left * &last_col).unwrap()), vec![0.0, 2.0, 15.0, 20.0]);
+        assert_eq!(values(&(&a - &lens.row(1).unwrap()).unwrap()), vec![-3.0, -3.0, -3.0, 0.0, 0.0, 0.0]);
+        assert_eq!(values(&(&a * 2.0)), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(values(&(&left + 1.0)), vec![1.0, 2.0, 4.0, 5.0]);
+        assert_eq!(values(&-&left), vec![-0.0, -1.0, -3.0, -4.0]);
+    }
+
+    #[test]
+    fn test_pow() {
+        let a = matrix();
+        assert_eq!(values(&a.powi(2)), vec![0.0, 1.0, 4.0, 9.0, 16.0, 25.0]);
+        assert_eq!(values(&a.powf(0.5))[4], 2.0);
+        let exponents = Matrix::from_shape_vec((2, 1), vec![0.0, 1.0]).unwrap();
+        assert_eq!(values(&a.pow(&exponents.as_lens()).unwrap()), vec![1.0, 1.0, 1.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_in_place() {
+        let mut a = matrix();
+        let row = Matrix::from_shape_vec((1, 3), vec![1.0, 2.0, 3.0]).unwrap();
+        let mut lens = a.as_lens_mut();
+        lens.try_mul_assign(&row.as_lens()).unwrap();
+        lens += 1.0;
+        lens /= 2.0;
+        assert_eq!(values(&a), vec![0.5, 1.5, 3.5, 2.0, 4.5, 8.0]);
+
+        // The target never grows to fit the right-hand side
+        let mut small = Matrix::from_shape_vec((1, 3), vec![1.0, 1.0, 1.0]).unwrap();
+        let tall = matrix();
+        assert!(small.as_lens_mut().try_add_assign(&tall.as_lens()).is_err());
+        assert_eq!(values(&small), vec![1.0, 1.0, 1.0]);
+    }
+}
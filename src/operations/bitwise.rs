@@ -0,0 +1,136 @@
+use crate::error::MatricalError;
+use crate::operations::arithmetic::{binary_op, zip_assign, zip_with};
+use crate::schematics::matrix::Matrix;
+use crate::strategies::lens::{Lens, LensMut};
+
+use num_traits::PrimInt;
+
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+// Element-wise bitwise operations
+//
+// For integer-valued Matrices and Lenses, such as packed status words or
+// sensor flags. The binary operators broadcast under the same rules as the
+// arithmetic ones and likewise output a `Result` between Matrices and Lenses.
+// Shifts take a scalar bit count, which must be less than the bit width of
+// the value type. Boolean flags are better held in a Mask,
+// which packs 64 cells into a word; see `operations::boolean`.
+//
+
+binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign, try_bitand_assign, "bitand");
+binary_op!(BitOr, bitor, BitOrAssign, bitor_assign, try_bitor_assign, "bitor");
+binary_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, try_bitxor_assign, "bitxor");
+
+impl<V: Clone + Not<Output = V>> Not for &Lens<'_, V> {
+    type Output = Matrix<V>;
+
+    fn not(self) -> Matrix<V> {
+        Matrix::from_array(self.view().map(|value| !value.clone()))
+    }
+}
+
+impl<V: Clone + Not<Output = V>> Not for &Matrix<V> {
+    type Output = Matrix<V>;
+
+    fn not(self) -> Matrix<V> {
+        !&self.as_lens()
+    }
+}
+
+// Reject shifts by at least the bit width of `V`, which would overflow
+fn check_shift<V: PrimInt>(bits: usize, operation: &'static str) -> Result<(), MatricalError> {
+    if bits >= V::zero().count_zeros() as usize {
+        return Err(MatricalError::InvalidValue { operation, index: None });
+    }
+    Ok(())
+}
+
+impl<V: PrimInt> Lens<'_, V> {
+    // Shift every value left by `bits`
+    pub fn shl(&self, bits: usize) -> Result<Matrix<V>, MatricalError> {
+        check_shift::<V>(bits, "shl")?;
+        Ok(Matrix::from_array(self.view().map(|value| value.shl(bits))))
+    }
+
+    // Shift every value right by `bits`
+    pub fn shr(&self, bits: usize) -> Result<Matrix<V>, MatricalError> {
+        check_shift::<V>(bits, "shr")?;
+        Ok(Matrix::from_array(self.view().map(|value| value.shr(bits))))
+    }
+
+    // Count the set bits of every value
+    pub fn count_ones(&self) -> Matrix<u32> {
+        Matrix::from_array(self.view().map(|value| value.count_ones()))
+    }
+}
+
+impl<V: PrimInt> Matrix<V> {
+    // Shift every value left by `bits`
+    pub fn shl(&self, bits: usize) -> Result<Matrix<V>, MatricalError> {
+        self.as_lens().shl(bits)
+    }
+
+    // Shift every value right by `bits`
+    pub fn shr(&self, bits: usize) -> Result<Matrix<V>, MatricalError> {
+        self.as_lens().shr(bits)
+    }
+
+    // Count the set bits of every value
+    pub fn count_ones(&self) -> Matrix<u32> {
+        self.as_lens().count_ones()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<V: Copy>(matrix: &Matrix<V>) -> Vec<V> {
+        matrix.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_binary_operators_broadcast() {
+        let flags = Matrix::from_shape_vec((2, 2), vec![0b1100u8, 0b1010, 0b0110, 0b1111]).unwrap();
+        let select = Matrix::from_shape_vec((1, 2), vec![0b0100u8, 0b0011]).unwrap();
+        assert_eq!(values(&(&flags & &select).unwrap()), vec![0b0100, 0b0010, 0b0100, 0b0011]);
+        assert_eq!(values(&(&flags | &select).unwrap()), vec![0b1100, 0b1011, 0b0110, 0b1111]);
+        assert_eq!(values(&(&flags ^ 0b1111)), vec![0b0011, 0b0101, 0b1001, 0b0000]);
+
+        let wide: Matrix<u8> = Matrix::zeros((2, 3)).unwrap();
+        assert!(matches!(
+            &flags & &wide,
+            Err(MatricalError::ShapeMismatch { operation: "bitand", .. })
+        ));
+    }
+
+    #[test]
+    fn test_not_shifts_and_popcount() {
+        let matrix = Matrix::from_shape_vec((1, 3), vec![0u8, 1, 0b1000_0001]).unwrap();
+        assert_eq!(values(&!&matrix), vec![0xff, 0xfe, 0b0111_1110]);
+        assert_eq!(values(&matrix.shl(1).unwrap()), vec![0, 2, 0b0000_0010]);
+        assert_eq!(values(&matrix.shr(7).unwrap()), vec![0, 0, 1]);
+        assert_eq!(values(&matrix.count_ones()), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_shifts_reject_overflowing_bit_counts() {
+        let matrix: Matrix<u8> = Matrix::from_shape_vec((1, 2), vec![1, 0xff]).unwrap();
+        assert!(matches!(matrix.shl(8), Err(MatricalError::InvalidValue { operation: "shl", .. })));
+        assert!(matches!(matrix.shr(8), Err(MatricalError::InvalidValue { operation: "shr", .. })));
+        assert!(matrix.shl(usize::MAX).is_err());
+
+        let wide: Matrix<u32> = Matrix::from_shape_vec((1, 1), vec![1]).unwrap();
+        assert_eq!(values(&wide.shl(8).unwrap()), vec![256]);
+    }
+
+    #[test]
+    fn test_in_place() {
+        let mut matrix = Matrix::from_fn((2, 2), |row, col| (row * 2 + col) as u16).unwrap();
+        let clear_low = Matrix::from_shape_vec((2, 1), vec![!1u16, !0]).unwrap();
+        let mut lens = matrix.as_lens_mut();
+        lens.try_bitand_assign(&clear_low.as_lens()).unwrap();
+        lens |= 0b100;
+        assert_eq!(values(&matrix), vec![4, 4, 6, 7]);
+    }
+}
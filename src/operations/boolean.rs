@@ -0,0 +1,222 @@
+use crate::error::MatricalError;
+use crate::schematics::mask::Mask;
+use crate::schematics::shape::{Axis, Shape};
+
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+// Boolean algebra over Masks
+//
+// Masks are bit-packed, so the combinators work a 64-bit word at a time
+// rather than cell by cell. Both operands must have the same shape; unlike
+// the arithmetic operators, Masks do not broadcast, since a row of flags
+// silently applied to every row is more often a bug than an intent. As with
+// the arithmetic operators, the `std::ops` impls between Masks output a
+// `Result`. Per-axis reductions follow `Axis`: `Axis::Column` yields one
+// value per column, `Axis::Row` one per row.
+//
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// Combine two Masks word by word
+fn zip_words<F>(lhs: &Mask, rhs: &Mask, operation: &'static str, f: F) -> Result<Mask, MatricalError>
+where
+    F: Fn(u64, u64) -> u64,
+{
+    check_shapes(lhs.shape(), rhs.shape(), operation)?;
+    let words = lhs
+        .as_words()
+        .iter()
+        .zip(rhs.as_words())
+        .map(|(&lhs, &rhs)| f(lhs, rhs))
+        .collect();
+    Ok(Mask::from_words(lhs.shape(), words))
+}
+
+fn check_shapes(lhs: Shape, rhs: Shape, operation: &'static str) -> Result<(), MatricalError> {
+    if lhs != rhs {
+        return Err(MatricalError::ShapeMismatch {
+            operation,
+            expected: lhs,
+            actual: rhs,
+        });
+    }
+    Ok(())
+}
+
+// Count the set bits at row-major offsets `start..end`
+fn count_range(words: &[u64], start: usize, end: usize) -> usize {
+    let mut count = 0;
+    let mut offset = start;
+    while offset < end {
+        let bit = offset % WORD_BITS;
+        let take = (WORD_BITS - bit).min(end - offset);
+        let bits = words[offset / WORD_BITS] >> bit;
+        let keep = if take == WORD_BITS { u64::MAX } else { (1 << take) - 1 };
+        count += (bits & keep).count_ones() as usize;
+        offset += take;
+    }
+    count
+}
+
+impl Mask {
+    // Get the cells set in both Masks
+    pub fn and(&self, rhs: &Mask) -> Result<Mask, MatricalError> {
+        zip_words(self, rhs, "and", |lhs, rhs| lhs & rhs)
+    }
+
+    // Get the cells set in either Mask
+    pub fn or(&self, rhs: &Mask) -> Result<Mask, MatricalError> {
+        zip_words(self, rhs, "or", |lhs, rhs| lhs | rhs)
+    }
+
+    // Get the cells set in exactly one of the Masks
+    pub fn xor(&self, rhs: &Mask) -> Result<Mask, MatricalError> {
+        zip_words(self, rhs, "xor", |lhs, rhs| lhs ^ rhs)
+    }
+
+    // Get the cells set in this Mask but not in `rhs`
+    pub fn and_not(&self, rhs: &Mask) -> Result<Mask, MatricalError> {
+        zip_words(self, rhs, "and_not", |lhs, rhs| lhs & !rhs)
+    }
+
+    // Get the complement of the Mask
+    pub fn not(&self) -> Mask {
+        Mask::from_words(self.shape(), self.as_words().iter().map(|word| !word).collect())
+    }
+
+    // Check whether any cell is set
+    pub fn any(&self) -> bool {
+        self.as_words().iter().any(|&word| word != 0)
+    }
+
+    // Check whether every cell is set; true for an empty Mask
+    pub fn all(&self) -> bool {
+        self.count_ones() == self.len()
+    }
+
+    // Count the set cells in each lane along `axis`
+    pub fn count_along(&self, axis: Axis) -> Vec<usize> {
+        let shape = self.shape();
+        match axis {
+            Axis::Row => (0..shape.rows())
+                .map(|row| count_range(self.as_words(), row * shape.cols(), (row + 1) * shape.cols()))
+                .collect(),
+            Axis::Column => {
+                let mut counts = vec![0; shape.cols()];
+                for index in self.iter_ones() {
+                    counts[index.col()] += 1;
+                }
+                counts
+            }
+        }
+    }
+
+    // Check whether any cell is set in each lane along `axis`
+    pub fn any_along(&self, axis: Axis) -> Vec<bool> {
+        self.count_along(axis).into_iter().map(|count| count > 0).collect()
+    }
+
+    // Check whether every cell is set in each lane along `axis`
+    pub fn all_along(&self, axis: Axis) -> Vec<bool> {
+        let len = match axis {
+            Axis::Row => self.shape().cols(),
+            Axis::Column => self.shape().rows(),
+        };
+        self.count_along(axis).into_iter().map(|count| count == len).collect()
+    }
+}
+
+impl BitAnd for &Mask {
+    type Output = Result<Mask, MatricalError>;
+
+    fn bitand(self, rhs: &Mask) -> Self::Output {
+        self.and(rhs)
+    }
+}
+
+impl BitOr for &Mask {
+    type Output = Result<Mask, MatricalError>;
+
+    fn bitor(self, rhs: &Mask) -> Self::Output {
+        self.or(rhs)
+    }
+}
+
+impl BitXor for &Mask {
+    type Output = Result<Mask, MatricalError>;
+
+    fn bitxor(self, rhs: &Mask) -> Self::Output {
+        self.xor(rhs)
+    }
+}
+
+impl Not for &Mask {
+    type Output = Mask;
+
+    fn not(self) -> Mask {
+        Mask::not(self)
+    }
+}
+
+impl Not for Mask {
+    type Output = Mask;
+
+    fn not(self) -> Mask {
+        Mask::not(&self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(rows: usize, cols: usize) -> Shape {
+        Shape::new(rows, cols).unwrap()
+    }
+
+    fn bits(mask: &Mask) -> Vec<bool> {
+        mask.iter().collect()
+    }
+
+    #[test]
+    fn test_combinators() {
+        let a = Mask::from_vec(shape(2, 2), vec![true, true, false, false]).unwrap();
+        let b = Mask::from_vec(shape(2, 2), vec![true, false, true, false]).unwrap();
+        assert_eq!(bits(&(&a & &b).unwrap()), vec![true, false, false, false]);
+        assert_eq!(bits(&(&a | &b).unwrap()), vec![true, true, true, false]);
+        assert_eq!(bits(&(&a ^ &b).unwrap()), vec![false, true, true, false]);
+        assert_eq!(bits(&a.and_not(&b).unwrap()), vec![false, true, false, false]);
+        assert_eq!(bits(&!&a), vec![false, false, true, true]);
+
+        let other = Mask::trues(shape(1, 4));
+        assert!(matches!(a.and(&other), Err(MatricalError::ShapeMismatch { operation: "and", .. })));
+    }
+
+    #[test]
+    fn test_not_keeps_padding_clear() {
+        let mask = !Mask::falses(shape(3, 25));
+        assert_eq!(mask.count_ones(), 75);
+        assert!(mask.all());
+        assert_eq!(mask, Mask::trues(shape(3, 25)));
+        assert!(!(!mask).any());
+    }
+
+    #[test]
+    fn test_reductions_along_axes() {
+        // Rows of 40 cells straddle word boundaries
+        let mask = Mask::from_fn(shape(3, 40), |row, col| row == 2 || col % (row + 2) == 0);
+        assert_eq!(mask.count_along(Axis::Row), vec![20, 14, 40]);
+        assert_eq!(mask.all_along(Axis::Row), vec![false, false, true]);
+
+        let counts = mask.count_along(Axis::Column);
+        assert_eq!(counts.len(), 40);
+        assert_eq!(&counts[..4], &[3, 1, 2, 2]);
+        assert_eq!(counts.iter().sum::<usize>(), mask.count_ones());
+        assert!(mask.any_along(Axis::Column).into_iter().all(|any| any));
+        assert_eq!(mask.all_along(Axis::Column)[..2], [true, false]);
+
+        let empty = Mask::falses(shape(0, 3));
+        assert!(empty.all() && !empty.any());
+        assert_eq!(empty.count_along(Axis::Column), vec![0, 0, 0]);
+    }
+}
@@ -1,49 +1,215 @@
+use crate::error::MatricalError;
+use crate::operations::mechanics::ValidationStrategy;
+use crate::schematics::mask::Mask;
+use crate::schematics::masked::MaskedMatrix;
+use crate::schematics::matrix::Matrix;
+use crate::strategies::gather::Gather;
+use crate::strategies::lens::{ColumnLens, Lens, RowLens};
+use crate::strategies::tag::TagSet;
+use crate::strategies::validity::MaskedLens;
 
+// Filtering
+//
+// Filters keep the rows or columns a predicate accepts, in their original
+// order, and return a borrowed Gather over them; call `to_matrix` on it for
+// an owned copy, which carries the Tags of the kept rows and columns along.
+// Predicates over a row can be plain closures or be built from the
+// ValidationStrategies in `mechanics` with `all_cells` and `cell_at`.
+//
 
+// Build a row predicate accepting rows in which every cell passes `strategy`
+pub fn all_cells<'s, V, S>(strategy: &'s S) -> impl Fn(RowLens<'_, V>) -> bool + 's
+where
+    V: 'static,
+    S: ValidationStrategy<V> + ?Sized,
+{
+    move |row| row.iter().all(|value| strategy.is_valid(value))
+}
 
+// Build a row predicate accepting rows whose cell in column `col` passes `strategy`
+pub fn cell_at<'s, V, S>(col: usize, strategy: &'s S) -> impl Fn(RowLens<'_, V>) -> bool + 's
+where
+    V: 'static,
+    S: ValidationStrategy<V> + ?Sized,
+{
+    move |row| row.get((0, col)).map(|value| strategy.is_valid(value)).unwrap_or(false)
+}
 
-// Defines a set of methods that can be used to filter a given data set
-// //
-// trait FilterStrategy: Strategy {
-//     fn filter(&self, data: &HashMap<String, String>) -> Result<(), String>;
-// }
-
-// // Defines a set of methods that can be used to filter a given data set
-// //
-// struct FilterStrategyImpl {
-//     data: HashMap<String, String>,
-// }
-
-// impl FilterStrategyImpl {
-//     fn new() -> Self {
-//         Self {
-//             data: HashMap::new(),
-//         }
-//     }
-// }
-
-// impl Strategy for FilterStrategyImpl {
-//     fn prepare(&self, data: &HashMap<String, String>) -> Result<(), String> {
-//         Ok(())
-//     }
-
-//     fn execute(&self, data: &HashMap<String, String>) -> Result<(), String> {
-//         Ok(())
-//     }
-
-//     fn result(&self) -> Result<(), String> {
-//         Ok(())
-//     }
-// }
-
-// impl FilterStrategy for FilterStrategyImpl {
-//     fn filter(&self, data: &HashMap<String, String>) -> Result<(), String> {
-//         Ok(())
-//     }
-// }
-
-// // Defines a set of methods that can be used to filter a given data set
-// //
-// struct FilterStrategyImpl2 {
-//     data: HashMap<String, String>,
-// }
\ No newline at end of file
+impl<'a, V> Lens<'a, V> {
+    // Gather the rows accepted by `predicate`
+    pub fn filter_rows<F>(&self, mut predicate: F) -> Result<Gather<'a, V>, MatricalError>
+    where
+        F: FnMut(RowLens<'a, V>) -> bool,
+    {
+        let mut rows = Vec::new();
+        for row in 0..self.rows() {
+            if predicate(self.row(row)?) {
+                rows.push(row);
+            }
+        }
+        self.select_rows(&rows)
+    }
+
+    // Gather the columns accepted by `predicate`
+    pub fn filter_cols<F>(&self, mut predicate: F) -> Result<Gather<'a, V>, MatricalError>
+    where
+        F: FnMut(ColumnLens<'a, V>) -> bool,
+    {
+        let mut cols = Vec::new();
+        for col in 0..self.cols() {
+            if predicate(self.col(col)?) {
+                cols.push(col);
+            }
+        }
+        self.select_cols(&cols)
+    }
+
+    // Gather the columns whose Tags are accepted by `predicate`
+    pub fn filter_cols_by_tag<F>(&self, mut predicate: F) -> Result<Gather<'a, V>, MatricalError>
+    where
+        F: FnMut(&TagSet) -> bool,
+    {
+        let mut cols = Vec::new();
+        for col in 0..self.cols() {
+            if predicate(self.col_tags(col)?) {
+                cols.push(col);
+            }
+        }
+        self.select_cols(&cols)
+    }
+}
+
+impl<V> Matrix<V> {
+    // Gather the rows accepted by `predicate`
+    pub fn filter_rows<'a, F>(&'a self, predicate: F) -> Result<Gather<'a, V>, MatricalError>
+    where
+        F: FnMut(RowLens<'a, V>) -> bool,
+    {
+        self.as_lens().filter_rows(predicate)
+    }
+
+    // Gather the columns accepted by `predicate`
+    pub fn filter_cols<'a, F>(&'a self, predicate: F) -> Result<Gather<'a, V>, MatricalError>
+    where
+        F: FnMut(ColumnLens<'a, V>) -> bool,
+    {
+        self.as_lens().filter_cols(predicate)
+    }
+
+    // Gather the columns whose Tags are accepted by `predicate`
+    pub fn filter_cols_by_tag<F>(&self, predicate: F) -> Result<Gather<'_, V>, MatricalError>
+    where
+        F: FnMut(&TagSet) -> bool,
+    {
+        self.as_lens().filter_cols_by_tag(predicate)
+    }
+}
+
+impl<'a, V> MaskedLens<'a, V> {
+    // Get the rows holding at most `max_invalid` invalid cells
+    fn rows_within(&self, max_invalid: usize) -> Result<Vec<usize>, MatricalError> {
+        let mut rows = Vec::new();
+        for row in 0..self.shape().rows() {
+            let mut invalid = 0;
+            for col in 0..self.shape().cols() {
+                if !self.is_valid((row, col))? {
+                    invalid += 1;
+                }
+            }
+            if invalid <= max_invalid {
+                rows.push(row);
+            }
+        }
+        Ok(rows)
+    }
+
+    // Gather the rows holding at most `max_invalid` invalid cells, with the
+    // validity of the gathered cells; placeholders under the invalid cells that
+    // remain are gathered too and must be read through the Mask
+    pub fn drop_invalid_rows(&self, max_invalid: usize) -> Result<(Gather<'a, V>, Mask), MatricalError> {
+        let rows = self.rows_within(max_invalid)?;
+        let gather = self.lens().select_rows(&rows)?;
+        let valid = Mask::from_fn(gather.shape(), |row, col| self.is_valid((rows[row], col)).unwrap_or(false));
+        Ok((gather, valid))
+    }
+}
+
+impl<V: Clone> MaskedMatrix<V> {
+    // Copy the rows holding at most `max_invalid` invalid cells, keeping their validity
+    pub fn drop_invalid_rows(&self, max_invalid: usize) -> Result<MaskedMatrix<V>, MatricalError> {
+        let (gather, valid) = self.as_lens().drop_invalid_rows(max_invalid)?;
+        MaskedMatrix::new(gather.to_matrix(), valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::mechanics::{Between, GreaterThan};
+    use crate::strategies::tag::Tag;
+
+    fn matrix() -> Matrix<f64> {
+        Matrix::from_shape_vec((4, 3), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, -8.0, 9.0, 0.5, 1.5, 2.5]).unwrap()
+    }
+
+    #[test]
+    fn test_filter_rows_with_closure() {
+        let matrix = matrix();
+        let gather = matrix.filter_rows(|row| row.iter().sum::<f64>() > 6.0).unwrap();
+        assert_eq!(gather.row_indices(), &[1, 2]);
+        let owned = gather.to_matrix();
+        assert_eq!(owned.get((1, 1)).unwrap(), &-8.0);
+
+        let none = matrix.filter_rows(|_| false).unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_filter_rows_with_validation_strategies() {
+        let matrix = matrix();
+        let positive = GreaterThan::new(0.0);
+        assert_eq!(matrix.filter_rows(all_cells(&positive)).unwrap().row_indices(), &[0, 1, 3]);
+
+        let small = Between::new(0.0, 2.0);
+        assert_eq!(matrix.filter_rows(cell_at(1, &small)).unwrap().row_indices(), &[0, 3]);
+        // A column outside the row never matches
+        assert!(matrix.filter_rows(cell_at(7, &small)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_filter_cols() {
+        let mut matrix = matrix();
+        let feature = Tag::custom("kind", "feature").unwrap();
+        matrix.col_tags_mut(0).unwrap().insert(feature.clone()).unwrap();
+        matrix.col_tags_mut(2).unwrap().insert(feature.clone()).unwrap();
+
+        let features = matrix.filter_cols_by_tag(|tags| tags.contains(&feature)).unwrap();
+        assert_eq!(features.col_indices(), &[0, 2]);
+        let owned = features.to_matrix();
+        assert_eq!(owned.cols(), 2);
+        assert!(owned.col_tags(1).unwrap().contains(&feature));
+
+        let lens = matrix.as_lens().row(0).unwrap();
+        assert_eq!(lens.filter_cols(|col| col.iter().all(|value| *value > 2.0)).unwrap().col_indices(), &[2]);
+    }
+
+    #[test]
+    fn test_drop_invalid_rows() {
+        let masked = MaskedMatrix::from_options(
+            (3, 2),
+            vec![Some(1.0), Some(2.0), None, Some(4.0), None, None],
+        )
+        .unwrap();
+        let (gather, valid) = masked.as_lens().drop_invalid_rows(0).unwrap();
+        assert_eq!(gather.row_indices(), &[0]);
+        assert_eq!(valid.count_ones(), 2);
+
+        // The invalid cell of a kept row stays invalid
+        let (gather, valid) = masked.as_lens().drop_invalid_rows(1).unwrap();
+        assert_eq!(gather.row_indices(), &[0, 1]);
+        assert_eq!(valid.iter().collect::<Vec<_>>(), vec![true, true, false, true]);
+
+        let kept = masked.drop_invalid_rows(1).unwrap();
+        assert_eq!(kept.iter().collect::<Vec<_>>(), vec![Some(&1.0), Some(&2.0), None, Some(&4.0)]);
+    }
+}
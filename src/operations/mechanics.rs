@@ -1,7 +1,10 @@
 
 
 
+use serde::{Deserialize, Serialize};
+
 use std::any::Any;
+use std::fmt::{self, Debug};
 use std::marker::PhantomData;
 
 pub struct Context {
@@ -100,19 +103,59 @@ impl<T: 'static> ValidationStrategy<T> for ComboValidationStrategy<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "combo"
+    }
+
+    fn failures(&self, input: &T, mode: ValidationMode, failures: &mut Vec<ValidationFailure>) {
+        let start = failures.len();
+        for (index, strategy) in self.static_strategies.iter().enumerate() {
+            if mode.is_done(failures) {
+                return;
+            }
+            nested(PathSegment::Static(index), failures, |failures| strategy.failures(input, mode, failures));
+        }
+
+        // Dynamic strategies are not run once a static strategy has failed
+        if failures.len() > start {
+            return;
+        }
+        for (index, strategy) in self.dynamic_strategies.iter().enumerate() {
+            if mode.is_done(failures) {
+                return;
+            }
+            nested(PathSegment::Dynamic(index), failures, |failures| strategy.failures(input, mode, failures));
+        }
+    }
 }
 
 pub struct NestedValidationStrategy {
     nested_validation: Validation<i32>,
 }
 
+impl NestedValidationStrategy {
+    // Create a new NestedValidationStrategy running a whole Validation as one strategy
+    pub fn new(nested_validation: Validation<i32>) -> Self {
+        NestedValidationStrategy { nested_validation }
+    }
+}
+
 impl ValidationStrategy<i32> for NestedValidationStrategy {
     fn is_valid(&self, data: &i32) -> bool {
         self.nested_validation.is_valid(data)
     }
 
     fn as_any(&self) -> &dyn Any {
-        todo!()
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "nested"
+    }
+
+    fn failures(&self, input: &i32, mode: ValidationMode, failures: &mut Vec<ValidationFailure>) {
+        self.nested_validation.collect_failures(input, mode, failures);
     }
 }
 
@@ -126,6 +169,10 @@ impl<T: 'static> ValidationStrategy<T> for AlwaysValid {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "always_valid"
+    }
 }
 
 // A validation strategy that always returns false
@@ -138,13 +185,26 @@ impl<T: 'static> ValidationStrategy<T> for AlwaysInvalid {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "always_invalid"
+    }
 }
 
 // A validation strategy that returns true if the input is equal to the given value
-pub struct Equals<T: 'static>(T);
+pub struct Equals<T: 'static>(T, Option<String>);
 impl<T: 'static + PartialEq> Equals<T> {
     pub fn new(value: T) -> Self {
-        Equals(value)
+        Equals(value, None)
+    }
+
+    // Report the bound in failures, for values that can be formatted
+    pub fn described(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.1 = Some(format!("== {:?}", self.0));
+        self
     }
 }
 
@@ -156,13 +216,30 @@ impl<T: 'static + PartialEq> ValidationStrategy<T> for Equals<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "equals"
+    }
+
+    fn expected(&self) -> Option<String> {
+        self.1.clone()
+    }
 }
 
 // A validation strategy that returns true if the input is not equal to the given value
-pub struct NotEquals<T: 'static>(T);
+pub struct NotEquals<T: 'static>(T, Option<String>);
 impl<T: 'static + PartialEq> NotEquals<T> {
     pub fn new(value: T) -> Self {
-        NotEquals(value)
+        NotEquals(value, None)
+    }
+
+    // Report the bound in failures, for values that can be formatted
+    pub fn described(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.1 = Some(format!("!= {:?}", self.0));
+        self
     }
 }
 impl<T: 'static + PartialEq> ValidationStrategy<T> for NotEquals<T> {
@@ -173,13 +250,30 @@ impl<T: 'static + PartialEq> ValidationStrategy<T> for NotEquals<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "not_equals"
+    }
+
+    fn expected(&self) -> Option<String> {
+        self.1.clone()
+    }
 }
 
 // A validation strategy that returns true if the input is greater than the given value
-pub struct GreaterThan<T: 'static>(T);
+pub struct GreaterThan<T: 'static>(T, Option<String>);
 impl<T: 'static + PartialOrd> GreaterThan<T> {
     pub fn new(value: T) -> Self {
-        GreaterThan(value)
+        GreaterThan(value, None)
+    }
+
+    // Report the bound in failures, for values that can be formatted
+    pub fn described(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.1 = Some(format!("> {:?}", self.0));
+        self
     }
 }
 impl<T: 'static + PartialOrd> ValidationStrategy<T> for GreaterThan<T> {
@@ -190,13 +284,30 @@ impl<T: 'static + PartialOrd> ValidationStrategy<T> for GreaterThan<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "greater_than"
+    }
+
+    fn expected(&self) -> Option<String> {
+        self.1.clone()
+    }
 }
 
 // A validation strategy that returns true if the input is greater than or equal to the given value
-pub struct GreaterThanOrEqual<T: 'static>(T);
+pub struct GreaterThanOrEqual<T: 'static>(T, Option<String>);
 impl<T: 'static + PartialOrd> GreaterThanOrEqual<T> {
     pub fn new(value: T) -> Self {
-        GreaterThanOrEqual(value)
+        GreaterThanOrEqual(value, None)
+    }
+
+    // Report the bound in failures, for values that can be formatted
+    pub fn described(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.1 = Some(format!(">= {:?}", self.0));
+        self
     }
 }
 impl<T: 'static + PartialOrd> ValidationStrategy<T> for GreaterThanOrEqual<T> {
@@ -207,13 +318,30 @@ impl<T: 'static + PartialOrd> ValidationStrategy<T> for GreaterThanOrEqual<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "greater_than_or_equal"
+    }
+
+    fn expected(&self) -> Option<String> {
+        self.1.clone()
+    }
 }
 
 // A validation strategy that returns true if the input is less than the given value
-pub struct LessThan<T: 'static>(T);
+pub struct LessThan<T: 'static>(T, Option<String>);
 impl<T: 'static + PartialOrd> LessThan<T> {
     pub fn new(value: T) -> Self {
-        LessThan(value)
+        LessThan(value, None)
+    }
+
+    // Report the bound in failures, for values that can be formatted
+    pub fn described(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.1 = Some(format!("< {:?}", self.0));
+        self
     }
 }
 impl<T: 'static + PartialOrd> ValidationStrategy<T> for LessThan<T> {
@@ -224,13 +352,30 @@ impl<T: 'static + PartialOrd> ValidationStrategy<T> for LessThan<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "less_than"
+    }
+
+    fn expected(&self) -> Option<String> {
+        self.1.clone()
+    }
 }
 
 // A validation strategy that returns true if the input is less than or equal to the given value
-pub struct LessThanOrEqual<T: 'static>(T);
+pub struct LessThanOrEqual<T: 'static>(T, Option<String>);
 impl<T: 'static + PartialOrd> LessThanOrEqual<T> {
     pub fn new(value: T) -> Self {
-        LessThanOrEqual(value)
+        LessThanOrEqual(value, None)
+    }
+
+    // Report the bound in failures, for values that can be formatted
+    pub fn described(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.1 = Some(format!("<= {:?}", self.0));
+        self
     }
 }
 impl<T: 'static + PartialOrd> ValidationStrategy<T> for LessThanOrEqual<T> {
@@ -241,13 +386,30 @@ impl<T: 'static + PartialOrd> ValidationStrategy<T> for LessThanOrEqual<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "less_than_or_equal"
+    }
+
+    fn expected(&self) -> Option<String> {
+        self.1.clone()
+    }
 }
 
 // A validation strategy that returns true if the input is between the given values
-pub struct Between<T: 'static>(T, T);
+pub struct Between<T: 'static>(T, T, Option<String>);
 impl<T: 'static + PartialOrd> Between<T> {
     pub fn new(min: T, max: T) -> Self {
-        Between(min, max)
+        Between(min, max, None)
+    }
+
+    // Report the bounds in failures, for values that can be formatted
+    pub fn described(mut self) -> Self
+    where
+        T: Debug,
+    {
+        self.2 = Some(format!("between {:?} and {:?}", self.0, self.1));
+        self
     }
 }
 impl<T: 'static + PartialOrd> ValidationStrategy<T> for Between<T> {
@@ -258,6 +420,14 @@ impl<T: 'static + PartialOrd> ValidationStrategy<T> for Between<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "between"
+    }
+
+    fn expected(&self) -> Option<String> {
+        self.2.clone()
+    }
 }
 
 
@@ -283,6 +453,209 @@ where
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+}
+
+// Combinators
+//
+// `a.and(b)`, `a.or(b)`, `a.xor(b)` and `a.not()` combine strategies into a
+// new strategy, so a bound like "between 0 and 1, or exactly the -1
+// sentinel" is `Between::new(0.0, 1.0).or(Equals::new(-1.0))`. `AtLeast`
+// passes when at least `n` of a list of boxed strategies pass, and `any_of`
+// and `all_of` build the two common cases. Every combinator is 'static and
+// returns itself from `as_any`, so it can be downcast, found with
+// `Validation::find_strategy` and removed with `remove_strategy::<S>` or
+// `retain_strategies` like any other strategy. An And
+// reports the failures of its operands; the others report one failure for
+// themselves, expecting the bounds of their operands.
+//
+
+// Describe a strategy by its expected bound, or by its name if it has none
+fn describe<T: 'static, S: ValidationStrategy<T> + ?Sized>(strategy: &S) -> String {
+    strategy.expected().unwrap_or_else(|| strategy.name().to_string())
+}
+
+// The And struct, passing when both operands pass
+pub struct And<A, B>(A, B);
+
+impl<A, B> And<A, B> {
+    // Create a new And
+    pub fn new(lhs: A, rhs: B) -> Self {
+        And(lhs, rhs)
+    }
+}
+
+impl<T: 'static, A, B> ValidationStrategy<T> for And<A, B>
+where
+    A: ValidationStrategy<T> + 'static,
+    B: ValidationStrategy<T> + 'static,
+{
+    fn is_valid(&self, input: &T) -> bool {
+        self.0.is_valid(input) && self.1.is_valid(input)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "and"
+    }
+
+    fn expected(&self) -> Option<String> {
+        Some(format!("{} and {}", describe(&self.0), describe(&self.1)))
+    }
+
+    fn failures(&self, input: &T, mode: ValidationMode, failures: &mut Vec<ValidationFailure>) {
+        nested(PathSegment::Operand(0), failures, |failures| self.0.failures(input, mode, failures));
+        if mode.is_done(failures) {
+            return;
+        }
+        nested(PathSegment::Operand(1), failures, |failures| self.1.failures(input, mode, failures));
+    }
+}
+
+// The Or struct, passing when either operand passes
+pub struct Or<A, B>(A, B);
+
+impl<A, B> Or<A, B> {
+    // Create a new Or
+    pub fn new(lhs: A, rhs: B) -> Self {
+        Or(lhs, rhs)
+    }
+}
+
+impl<T: 'static, A, B> ValidationStrategy<T> for Or<A, B>
+where
+    A: ValidationStrategy<T> + 'static,
+    B: ValidationStrategy<T> + 'static,
+{
+    fn is_valid(&self, input: &T) -> bool {
+        self.0.is_valid(input) || self.1.is_valid(input)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "or"
+    }
+
+    fn expected(&self) -> Option<String> {
+        Some(format!("{} or {}", describe(&self.0), describe(&self.1)))
+    }
+}
+
+// The Xor struct, passing when exactly one operand passes
+pub struct Xor<A, B>(A, B);
+
+impl<A, B> Xor<A, B> {
+    // Create a new Xor
+    pub fn new(lhs: A, rhs: B) -> Self {
+        Xor(lhs, rhs)
+    }
+}
+
+impl<T: 'static, A, B> ValidationStrategy<T> for Xor<A, B>
+where
+    A: ValidationStrategy<T> + 'static,
+    B: ValidationStrategy<T> + 'static,
+{
+    fn is_valid(&self, input: &T) -> bool {
+        self.0.is_valid(input) != self.1.is_valid(input)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "xor"
+    }
+
+    fn expected(&self) -> Option<String> {
+        Some(format!("exactly one of {}, {}", describe(&self.0), describe(&self.1)))
+    }
+}
+
+// The Not struct, passing when its operand fails
+pub struct Not<S>(S);
+
+impl<S> Not<S> {
+    // Create a new Not
+    pub fn new(strategy: S) -> Self {
+        Not(strategy)
+    }
+}
+
+impl<T: 'static, S: ValidationStrategy<T> + 'static> ValidationStrategy<T> for Not<S> {
+    fn is_valid(&self, input: &T) -> bool {
+        !self.0.is_valid(input)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "not"
+    }
+
+    fn expected(&self) -> Option<String> {
+        Some(format!("not {}", describe(&self.0)))
+    }
+}
+
+// The AtLeast struct, passing when at least `n` of its strategies pass
+pub struct AtLeast<T: 'static> {
+    n: usize,
+    strategies: Vec<Box<dyn ValidationStrategy<T>>>,
+}
+
+impl<T: 'static> AtLeast<T> {
+    // Create a new AtLeast
+    pub fn new(n: usize, strategies: Vec<Box<dyn ValidationStrategy<T>>>) -> Self {
+        AtLeast { n, strategies }
+    }
+
+    // Get the number of strategies that must pass
+    pub fn n(&self) -> usize {
+        self.n
+    }
+}
+
+impl<T: 'static> ValidationStrategy<T> for AtLeast<T> {
+    fn is_valid(&self, input: &T) -> bool {
+        // Stop counting as soon as enough strategies have passed
+        self.n == 0 || self.strategies.iter().filter(|strategy| strategy.is_valid(input)).nth(self.n - 1).is_some()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "at_least"
+    }
+
+    fn expected(&self) -> Option<String> {
+        let operands: Vec<String> = self.strategies.iter().map(|strategy| describe(strategy.as_ref())).collect();
+        Some(format!("at least {} of [{}]", self.n, operands.join(", ")))
+    }
+}
+
+// Build a strategy passing when any of `strategies` passes
+pub fn any_of<T: 'static>(strategies: Vec<Box<dyn ValidationStrategy<T>>>) -> AtLeast<T> {
+    AtLeast::new(1, strategies)
+}
+
+// Build a strategy passing when all of `strategies` pass
+pub fn all_of<T: 'static>(strategies: Vec<Box<dyn ValidationStrategy<T>>>) -> AtLeast<T> {
+    AtLeast::new(strategies.len(), strategies)
 }
 
 
@@ -314,6 +687,207 @@ impl ValidationStrategy<i32> for NumberValidation {
 pub trait ValidationStrategy<T: 'static> {
     fn is_valid(&self, input: &T) -> bool;
     fn as_any(&self) -> &dyn Any;
+
+    // Get the name the strategy is reported under
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    // Describe the bound an input must satisfy, if the strategy has one
+    fn expected(&self) -> Option<String> {
+        None
+    }
+
+    // Record why `input` fails this strategy; composite strategies record
+    // the failures of their parts, stopping at the first one in short-circuit mode
+    fn failures(&self, input: &T, _mode: ValidationMode, failures: &mut Vec<ValidationFailure>) {
+        if !self.is_valid(input) {
+            failures.push(ValidationFailure::new(self.name(), self.expected()));
+        }
+    }
+
+    // Pass when both this strategy and `other` pass
+    fn and<S>(self, other: S) -> And<Self, S>
+    where
+        Self: Sized,
+    {
+        And::new(self, other)
+    }
+
+    // Pass when this strategy or `other` passes
+    fn or<S>(self, other: S) -> Or<Self, S>
+    where
+        Self: Sized,
+    {
+        Or::new(self, other)
+    }
+
+    // Pass when exactly one of this strategy and `other` passes
+    fn xor<S>(self, other: S) -> Xor<Self, S>
+    where
+        Self: Sized,
+    {
+        Xor::new(self, other)
+    }
+
+    // Pass when this strategy fails
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not::new(self)
+    }
+}
+
+// How much of a Validation runs once an input has failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ValidationMode {
+    // Stop at the first failure
+    #[default]
+    ShortCircuit,
+    // Run every strategy and record every failure
+    CollectAll,
+}
+
+impl ValidationMode {
+    fn is_done(self, failures: &[ValidationFailure]) -> bool {
+        self == ValidationMode::ShortCircuit && !failures.is_empty()
+    }
+}
+
+// One step from a Validation down to the strategy that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathSegment {
+    // The strategy at this position of a Validation or Validator
+    Strategy(usize),
+    // The child Validation at this position
+    Child(usize),
+    // The static strategy at this position of a ComboValidationStrategy
+    Static(usize),
+    // The dynamic strategy at this position of a ComboValidationStrategy
+    Dynamic(usize),
+    // The operand at this position of a combinator
+    Operand(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Strategy(index) => write!(f, "strategies[{}]", index),
+            PathSegment::Child(index) => write!(f, "children[{}]", index),
+            PathSegment::Static(index) => write!(f, "static[{}]", index),
+            PathSegment::Dynamic(index) => write!(f, "dynamic[{}]", index),
+            PathSegment::Operand(index) => write!(f, "operands[{}]", index),
+        }
+    }
+}
+
+// A single failed strategy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationFailure {
+    // The name of the strategy that failed
+    strategy: String,
+    // The bound it expected, if it has one
+    expected: Option<String>,
+    // The position of the input in a batch
+    position: Option<usize>,
+    // The path from the Validation down to the strategy
+    path: Vec<PathSegment>,
+}
+
+impl ValidationFailure {
+    // Create a new ValidationFailure for a strategy, at the root of its path
+    pub fn new(strategy: impl Into<String>, expected: Option<String>) -> Self {
+        Self {
+            strategy: strategy.into(),
+            expected,
+            position: None,
+            path: Vec::new(),
+        }
+    }
+
+    // Get the name of the strategy that failed
+    pub fn strategy(&self) -> &str {
+        &self.strategy
+    }
+
+    // Get the bound the strategy expected
+    pub fn expected(&self) -> Option<&str> {
+        self.expected.as_deref()
+    }
+
+    // Get the position of the input in a batch
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    // Get the path from the Validation down to the strategy
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+}
+
+impl fmt::Display for ValidationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.path {
+            write!(f, "{}.", segment)?;
+        }
+        write!(f, "{} failed", self.strategy)?;
+        if let Some(position) = self.position {
+            write!(f, " at position {}", position)?;
+        }
+        if let Some(expected) = &self.expected {
+            write!(f, ": expected {}", expected)?;
+        }
+        Ok(())
+    }
+}
+
+// Run `f` and prefix the failures it records with `segment`
+fn nested<F>(segment: PathSegment, failures: &mut Vec<ValidationFailure>, f: F)
+where
+    F: FnOnce(&mut Vec<ValidationFailure>),
+{
+    let start = failures.len();
+    f(failures);
+    for failure in &mut failures[start..] {
+        failure.path.insert(0, segment);
+    }
+}
+
+// The ValidationReport struct
+//
+// The outcome of a detailed validation: how many inputs were checked and
+// every failure recorded, in the order the strategies ran. In short-circuit
+// mode the report holds at most one failure and stops counting at the input
+// that produced it.
+//
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    checked: usize,
+    failures: Vec<ValidationFailure>,
+}
+
+impl ValidationReport {
+    // Check whether every checked input passed
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    // Get the number of inputs checked
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    // Get the recorded failures
+    pub fn failures(&self) -> &[ValidationFailure] {
+        &self.failures
+    }
+
+    // Get the first recorded failure
+    pub fn first_failure(&self) -> Option<&ValidationFailure> {
+        self.failures.first()
+    }
 }
 
 pub struct Validation<T: 'static> {
@@ -349,8 +923,24 @@ impl<T: 'static> Validation<T> {
         self.children.push(child);
     }
 
-    pub fn remove_strategy(&mut self, strategy: &dyn Any) {
-        self.strategies.retain(|s| !std::ptr::eq(s.as_any(), strategy));
+    // Remove the first strategy of type `S`, returning it; strategies are matched
+    // by type because boxed zero-sized strategies all share one address
+    pub fn remove_strategy<S: 'static>(&mut self) -> Option<Box<dyn ValidationStrategy<T>>> {
+        let index = self.strategies.iter().position(|strategy| strategy.as_any().is::<S>())?;
+        Some(self.strategies.remove(index))
+    }
+
+    // Get the first strategy of type `S`
+    pub fn find_strategy<S: 'static>(&self) -> Option<&S> {
+        self.strategies.iter().find_map(|strategy| strategy.as_any().downcast_ref::<S>())
+    }
+
+    // Keep only the strategies accepted by `keep`
+    pub fn retain_strategies<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&dyn ValidationStrategy<T>) -> bool,
+    {
+        self.strategies.retain(|strategy| keep(strategy.as_ref()));
     }
 
     pub fn remove_child(&mut self, child: &Validation<T>) {
@@ -365,6 +955,45 @@ impl<T: 'static> Validation<T> {
         self.strategies.iter().all(|strategy| strategy.is_valid(input)) &&
         self.children.iter().all(|child| child.is_valid(input))
     }
+
+    // Validate an input, reporting which strategies and children failed
+    pub fn validate_detailed(&self, input: &T, mode: ValidationMode) -> ValidationReport {
+        let mut failures = Vec::new();
+        self.collect_failures(input, mode, &mut failures);
+        ValidationReport { checked: 1, failures }
+    }
+
+    // Validate a batch of inputs, reporting the position of each failing input
+    pub fn batch_validate_detailed(&self, inputs: &[T], mode: ValidationMode) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for (position, input) in inputs.iter().enumerate() {
+            if mode.is_done(&report.failures) {
+                break;
+            }
+            let start = report.failures.len();
+            self.collect_failures(input, mode, &mut report.failures);
+            for failure in &mut report.failures[start..] {
+                failure.position = Some(position);
+            }
+            report.checked += 1;
+        }
+        report
+    }
+
+    pub(crate) fn collect_failures(&self, input: &T, mode: ValidationMode, failures: &mut Vec<ValidationFailure>) {
+        for (index, strategy) in self.strategies.iter().enumerate() {
+            if mode.is_done(failures) {
+                return;
+            }
+            nested(PathSegment::Strategy(index), failures, |failures| strategy.failures(input, mode, failures));
+        }
+        for (index, child) in self.children.iter().enumerate() {
+            if mode.is_done(failures) {
+                return;
+            }
+            nested(PathSegment::Child(index), failures, |failures| child.collect_failures(input, mode, failures));
+        }
+    }
 }
 
 
@@ -417,14 +1046,31 @@ impl<T: 'static> Validator<T> {
         }
     }
 
-    pub fn remove_strategy(&mut self, strategy: &dyn Any) {
-        self.strategies.retain(|s| !std::ptr::eq(s.as_any(), strategy));
+    // Remove the first strategy of type `S`, returning it; strategies are matched
+    // by type because boxed zero-sized strategies all share one address
+    pub fn remove_strategy<S: 'static>(&mut self) -> Option<Box<dyn ValidationStrategy<T>>> {
+        let index = self.strategies.iter().position(|strategy| strategy.as_any().is::<S>())?;
+        Some(self.strategies.remove(index))
     }
 
 
     pub fn validate(&self) -> bool {
         self.strategies.iter().all(|strategy| strategy.is_valid(&self.data))
     }
+
+    // Validate the data, reporting which strategies failed
+    pub fn validate_detailed(&self, mode: ValidationMode) -> ValidationReport {
+        let mut failures = Vec::new();
+        for (index, strategy) in self.strategies.iter().enumerate() {
+            if mode.is_done(&failures) {
+                break;
+            }
+            nested(PathSegment::Strategy(index), &mut failures, |failures| {
+                strategy.failures(&self.data, mode, failures)
+            });
+        }
+        ValidationReport { checked: 1, failures }
+    }
 }
 
 
@@ -450,8 +1096,8 @@ impl<T: 'static> ValidatorFactory<T> {
         self.validators.retain(|v| !std::ptr::eq(v, validator));
     }
 
-    pub fn remove_strategy(&mut self, validator: &mut Validation<T>, strategy: &dyn Any) {
-        validator.remove_strategy(strategy);
+    pub fn remove_strategy<S: 'static>(&mut self, validator: &mut Validation<T>) -> Option<Box<dyn ValidationStrategy<T>>> {
+        validator.remove_strategy::<S>()
     }
 }
 
@@ -654,6 +1300,185 @@ mod tests {
         assert!(!strategy.is_valid(&5));
     }
 
+    #[test]
+    fn test_validate_detailed() {
+        let mut validation = Validation::new();
+        validation.add_strategy(Between::new(0.0, 1.0).described());
+        validation.add_strategy(GreaterThan::new(0.5));
+
+        let report = validation.validate_detailed(&0.7, ValidationMode::CollectAll);
+        assert!(report.is_valid());
+        assert_eq!(report.checked(), 1);
+
+        let report = validation.validate_detailed(&1.5, ValidationMode::CollectAll);
+        let failure = report.first_failure().unwrap();
+        assert_eq!(failure.strategy(), "between");
+        assert_eq!(failure.expected(), Some("between 0.0 and 1.0"));
+        assert_eq!(failure.path(), &[PathSegment::Strategy(0)]);
+        assert_eq!(failure.to_string(), "strategies[0].between failed: expected between 0.0 and 1.0");
+    }
+
+    #[test]
+    fn test_collect_all_and_short_circuit() {
+        let mut inner = Validation::new();
+        inner.add_strategy(LessThan::new(-1));
+        let mut validation = Validation::new();
+        validation.add_strategy(Equals::new(5));
+        validation.add_strategy(AlwaysInvalid);
+        validation.add_child(inner);
+
+        let all = validation.validate_detailed(&6, ValidationMode::CollectAll);
+        let names: Vec<_> = all.failures().iter().map(|failure| failure.strategy()).collect();
+        assert_eq!(names, vec!["equals", "always_invalid", "less_than"]);
+        assert_eq!(all.failures()[2].path(), &[PathSegment::Child(0), PathSegment::Strategy(0)]);
+
+        let first = validation.validate_detailed(&6, ValidationMode::ShortCircuit);
+        assert_eq!(first.failures().len(), 1);
+        assert_eq!(first.failures()[0].strategy(), "equals");
+    }
+
+    #[test]
+    fn test_combo_failures_skip_dynamic_strategies() {
+        let combo: ComboValidationStrategy<i32> = ComboValidationStrategy::new(
+            vec![Box::new(GreaterThan::new(0))],
+            vec![Box::new(LessThan::new(10)), Box::new(NotEquals::new(5))],
+        );
+        let mut failures = Vec::new();
+        combo.failures(&-1, ValidationMode::CollectAll, &mut failures);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].path(), &[PathSegment::Static(0)]);
+
+        failures.clear();
+        combo.failures(&5, ValidationMode::CollectAll, &mut failures);
+        assert_eq!(failures[0].strategy(), "not_equals");
+        assert_eq!(failures[0].path(), &[PathSegment::Dynamic(1)]);
+    }
+
+    #[test]
+    fn test_batch_validate_detailed() {
+        let mut validation = Validation::new();
+        validation.add_strategy(GreaterThanOrEqual::new(0));
+        let inputs = [3, -1, 4, -2];
+
+        let all = validation.batch_validate_detailed(&inputs, ValidationMode::CollectAll);
+        assert_eq!(all.checked(), 4);
+        let positions: Vec<_> = all.failures().iter().map(|failure| failure.position()).collect();
+        assert_eq!(positions, vec![Some(1), Some(3)]);
+
+        let first = validation.batch_validate_detailed(&inputs, ValidationMode::ShortCircuit);
+        assert_eq!(first.checked(), 2);
+        assert_eq!(first.failures().len(), 1);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let unit_or_sentinel = Between::new(0.0, 1.0).described().or(Equals::new(-1.0).described());
+        assert!(unit_or_sentinel.is_valid(&0.5));
+        assert!(unit_or_sentinel.is_valid(&-1.0));
+        assert!(!unit_or_sentinel.is_valid(&-0.5));
+        assert_eq!(unit_or_sentinel.expected().unwrap(), "between 0.0 and 1.0 or == -1.0");
+
+        let inside = GreaterThan::new(0).and(LessThan::new(10));
+        assert!(inside.is_valid(&5));
+        assert!(!inside.is_valid(&10));
+        assert!(inside.not().is_valid(&10));
+
+        let one_side = GreaterThan::new(0).xor(GreaterThan::new(5));
+        assert!(one_side.is_valid(&3));
+        assert!(!one_side.is_valid(&7));
+        assert!(!one_side.is_valid(&-1));
+
+        // Without a description a combinator falls back to the operand names
+        assert_eq!(Between::new(0.0, 1.0).or(Equals::new(-1.0)).expected().unwrap(), "between or equals");
+    }
+
+    #[test]
+    fn test_comparisons_without_debug() {
+        #[derive(PartialEq, PartialOrd)]
+        struct Level(u8);
+
+        let strategy = Between::new(Level(1), Level(3));
+        assert!(strategy.is_valid(&Level(2)));
+        assert!(!strategy.is_valid(&Level(4)));
+        assert_eq!(ValidationStrategy::<Level>::expected(&strategy), None);
+
+        let mut failures = Vec::new();
+        strategy.failures(&Level(4), ValidationMode::CollectAll, &mut failures);
+        assert_eq!(failures[0].strategy(), "between");
+        assert_eq!(failures[0].expected(), None);
+    }
+
+    #[test]
+    fn test_at_least() {
+        let two: AtLeast<i32> = AtLeast::new(
+            2,
+            vec![
+                Box::new(GreaterThan::new(0).described()),
+                Box::new(LessThan::new(10).described()),
+                Box::new(NotEquals::new(5).described()),
+            ],
+        );
+        assert!(two.is_valid(&5));
+        assert!(two.is_valid(&12));
+        assert!(!AtLeast::<i32>::new(3, vec![Box::new(GreaterThan::new(0)), Box::new(LessThan::new(10))]).is_valid(&5));
+        assert_eq!(two.expected().unwrap(), "at least 2 of [> 0, < 10, != 5]");
+
+        let any = any_of::<i32>(vec![Box::new(Equals::new(1)), Box::new(Equals::new(2))]);
+        assert!(any.is_valid(&2));
+        assert!(!any.is_valid(&3));
+
+        let all = all_of::<i32>(vec![Box::new(GreaterThan::new(0)), Box::new(NotEquals::new(5))]);
+        assert!(all.is_valid(&4));
+        assert!(!all.is_valid(&5));
+        assert!(all_of::<i32>(Vec::new()).is_valid(&5));
+        assert!(!any_of::<i32>(Vec::new()).is_valid(&5));
+    }
+
+    #[test]
+    fn test_combinator_failures() {
+        let inside = GreaterThan::new(0).and(LessThan::new(10));
+        let mut failures = Vec::new();
+        inside.failures(&10, ValidationMode::CollectAll, &mut failures);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].strategy(), "less_than");
+        assert_eq!(failures[0].path(), &[PathSegment::Operand(1)]);
+
+        let mut validation = Validation::new();
+        validation.add_strategy(Between::new(0.0, 1.0).or(Equals::new(-1.0)));
+        let report = validation.validate_detailed(&2.0, ValidationMode::CollectAll);
+        assert_eq!(report.failures().len(), 1);
+        assert_eq!(report.failures()[0].strategy(), "or");
+    }
+
+    #[test]
+    fn test_combinators_downcast_and_remove() {
+        let mut validation = Validation::new();
+        validation.add_strategy(GreaterThan::new(0));
+        validation.add_strategy(GreaterThan::new(0).not());
+        validation.add_strategy(NestedValidationStrategy::new(Validation::new()));
+        assert!(!validation.is_valid(&5));
+
+        assert!(validation.find_strategy::<Not<GreaterThan<i32>>>().is_some());
+        assert!(validation.find_strategy::<NestedValidationStrategy>().is_some());
+        assert!(validation.find_strategy::<Or<GreaterThan<i32>, LessThan<i32>>>().is_none());
+
+        let removed = validation.remove_strategy::<Not<GreaterThan<i32>>>().unwrap();
+        assert!(removed.as_any().is::<Not<GreaterThan<i32>>>());
+        assert!(validation.find_strategy::<Not<GreaterThan<i32>>>().is_none());
+        assert!(validation.remove_strategy::<Not<GreaterThan<i32>>>().is_none());
+        assert!(validation.is_valid(&5));
+
+        // Only the first strategy of the type goes, even for zero-sized ones
+        validation.add_strategy(ValidationStrategy::<i32>::or(AlwaysInvalid, AlwaysValid));
+        validation.add_strategy(ValidationStrategy::<i32>::or(AlwaysInvalid, AlwaysValid));
+        assert!(validation.remove_strategy::<Or<AlwaysInvalid, AlwaysValid>>().is_some());
+        assert!(validation.find_strategy::<Or<AlwaysInvalid, AlwaysValid>>().is_some());
+
+        validation.retain_strategies(|strategy| !strategy.as_any().is::<Or<AlwaysInvalid, AlwaysValid>>());
+        assert!(validation.find_strategy::<Or<AlwaysInvalid, AlwaysValid>>().is_none());
+        assert!(validation.find_strategy::<GreaterThan<i32>>().is_some());
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -6,6 +6,8 @@ pub mod mechanics;
 pub mod bitwise;
 pub mod boolean;
 pub mod filter;
+pub mod relational;
+pub mod schema;
 pub mod sort;
 
 pub use arithmetic::*;
@@ -14,6 +16,8 @@ pub use mechanics::*;
 pub use bitwise::*;
 pub use boolean::*;
 pub use filter::*;
+pub use relational::*;
+pub use schema::*;
 pub use sort::*;
 
 use crate::{ElementContext, error::MatricalError};
@@ -0,0 +1,309 @@
+use crate::error::MatricalError;
+use crate::operations::arithmetic::{broadcast_index, broadcast_shape};
+use crate::schematics::mask::Mask;
+use crate::schematics::masked::MaskedMatrix;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::Shape;
+use crate::strategies::lens::Lens;
+
+use ndarray::IndexLonger;
+use num_traits::Float;
+
+// Element-wise comparisons
+//
+// Every comparison produces a Mask with the shape of the left-hand side, so
+// its result can be handed straight to `Lens::masked` or combined with the
+// validity Mask of a MaskedMatrix. The right-hand side is a scalar, or a
+// Matrix or Lens that broadcasts to the left-hand side under the arithmetic
+// rules; it is never grown to fit. Comparisons involving NaN are false, except
+// `elem_ne`, which is true.
+//
+
+// The right-hand side of a comparison
+pub trait Comparand<V> {
+    // Check that the operand broadcasts to a left-hand side of `shape`
+    fn check(&self, shape: Shape, operation: &'static str) -> Result<(), MatricalError>;
+
+    // Get the value compared against cell (row, col) of the left-hand side
+    fn value(&self, row: usize, col: usize) -> &V;
+}
+
+impl<V> Comparand<V> for V {
+    fn check(&self, _shape: Shape, _operation: &'static str) -> Result<(), MatricalError> {
+        Ok(())
+    }
+
+    fn value(&self, _row: usize, _col: usize) -> &V {
+        self
+    }
+}
+
+impl<V> Comparand<V> for &Lens<'_, V> {
+    fn check(&self, shape: Shape, operation: &'static str) -> Result<(), MatricalError> {
+        if broadcast_shape(shape, self.shape(), operation)? != shape {
+            return Err(MatricalError::ShapeMismatch {
+                operation,
+                expected: shape,
+                actual: self.shape(),
+            });
+        }
+        Ok(())
+    }
+
+    fn value(&self, row: usize, col: usize) -> &V {
+        let view = self.view();
+        let index = broadcast_index(view.dim(), row, col);
+        IndexLonger::index(&view, index)
+    }
+}
+
+impl<V> Comparand<V> for &Matrix<V> {
+    fn check(&self, shape: Shape, operation: &'static str) -> Result<(), MatricalError> {
+        Comparand::<V>::check(&&self.as_lens(), shape, operation)
+    }
+
+    fn value(&self, row: usize, col: usize) -> &V {
+        let index = broadcast_index(self.as_array().dim(), row, col);
+        &self.as_array()[index]
+    }
+}
+
+// Build the Mask of the cells of `lhs` for which `f` holds against `rhs`
+fn compare<V, R, F>(lhs: &Lens<'_, V>, rhs: &R, operation: &'static str, f: F) -> Result<Mask, MatricalError>
+where
+    R: Comparand<V> + ?Sized,
+    F: Fn(&V, &V) -> bool,
+{
+    rhs.check(lhs.shape(), operation)?;
+    let view = lhs.view();
+    Ok(Mask::from_fn(lhs.shape(), |row, col| f(&view[(row, col)], rhs.value(row, col))))
+}
+
+impl<V> Lens<'_, V> {
+    // Mark the cells equal to `rhs`
+    pub fn elem_eq<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialEq,
+    {
+        compare(self, &rhs, "eq", |lhs, rhs| lhs == rhs)
+    }
+
+    // Mark the cells not equal to `rhs`
+    pub fn elem_ne<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialEq,
+    {
+        compare(self, &rhs, "ne", |lhs, rhs| lhs != rhs)
+    }
+
+    // Mark the cells less than `rhs`
+    pub fn elem_lt<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        compare(self, &rhs, "lt", |lhs, rhs| lhs < rhs)
+    }
+
+    // Mark the cells less than or equal to `rhs`
+    pub fn elem_le<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        compare(self, &rhs, "le", |lhs, rhs| lhs <= rhs)
+    }
+
+    // Mark the cells greater than `rhs`
+    pub fn elem_gt<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        compare(self, &rhs, "gt", |lhs, rhs| lhs > rhs)
+    }
+
+    // Mark the cells greater than or equal to `rhs`
+    pub fn elem_ge<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        compare(self, &rhs, "ge", |lhs, rhs| lhs >= rhs)
+    }
+
+    // Mark the cells within `low..=high`
+    pub fn between<L: Comparand<V>, H: Comparand<V>>(&self, low: L, high: H) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        low.check(self.shape(), "between")?;
+        high.check(self.shape(), "between")?;
+        let view = self.view();
+        Ok(Mask::from_fn(self.shape(), |row, col| {
+            let value = &view[(row, col)];
+            low.value(row, col) <= value && value <= high.value(row, col)
+        }))
+    }
+
+    // Mark the cells within `abs_tol`, or `rel_tol` times the larger magnitude, of `rhs`
+    pub fn approx_eq<R: Comparand<V>>(&self, rhs: R, abs_tol: V, rel_tol: V) -> Result<Mask, MatricalError>
+    where
+        V: Float,
+    {
+        compare(self, &rhs, "approx_eq", |lhs, rhs| {
+            lhs == rhs || (*lhs - *rhs).abs() <= abs_tol.max(rel_tol * lhs.abs().max(rhs.abs()))
+        })
+    }
+}
+
+impl<V> Matrix<V> {
+    // Mark the cells equal to `rhs`
+    pub fn elem_eq<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialEq,
+    {
+        self.as_lens().elem_eq(rhs)
+    }
+
+    // Mark the cells not equal to `rhs`
+    pub fn elem_ne<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialEq,
+    {
+        self.as_lens().elem_ne(rhs)
+    }
+
+    // Mark the cells less than `rhs`
+    pub fn elem_lt<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        self.as_lens().elem_lt(rhs)
+    }
+
+    // Mark the cells less than or equal to `rhs`
+    pub fn elem_le<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        self.as_lens().elem_le(rhs)
+    }
+
+    // Mark the cells greater than `rhs`
+    pub fn elem_gt<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        self.as_lens().elem_gt(rhs)
+    }
+
+    // Mark the cells greater than or equal to `rhs`
+    pub fn elem_ge<R: Comparand<V>>(&self, rhs: R) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        self.as_lens().elem_ge(rhs)
+    }
+
+    // Mark the cells within `low..=high`
+    pub fn between<L: Comparand<V>, H: Comparand<V>>(&self, low: L, high: H) -> Result<Mask, MatricalError>
+    where
+        V: PartialOrd,
+    {
+        self.as_lens().between(low, high)
+    }
+
+    // Mark the cells within `abs_tol`, or `rel_tol` times the larger magnitude, of `rhs`
+    pub fn approx_eq<R: Comparand<V>>(&self, rhs: R, abs_tol: V, rel_tol: V) -> Result<Mask, MatricalError>
+    where
+        V: Float,
+    {
+        self.as_lens().approx_eq(rhs, abs_tol, rel_tol)
+    }
+}
+
+impl<V> MaskedMatrix<V> {
+    // Mark invalid every cell set in `mask`, such as the result of a comparison
+    pub fn invalidate_where(&mut self, mask: &Mask) -> Result<(), MatricalError> {
+        if mask.shape() != self.shape() {
+            return Err(MatricalError::ShapeMismatch {
+                operation: "invalidate_where",
+                expected: self.shape(),
+                actual: mask.shape(),
+            });
+        }
+        for index in mask.iter_ones() {
+            self.invalidate(index)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::shape::{Index, Region};
+
+    fn matrix() -> Matrix<f64> {
+        Matrix::from_fn((2, 3), |row, col| (row * 3 + col) as f64).unwrap()
+    }
+
+    fn bits(mask: &Mask) -> Vec<bool> {
+        mask.iter().collect()
+    }
+
+    #[test]
+    fn test_scalar_comparisons() {
+        let matrix = matrix();
+        assert_eq!(bits(&matrix.elem_eq(2.0).unwrap()), vec![false, false, true, false, false, false]);
+        assert_eq!(matrix.elem_ne(2.0).unwrap().count_ones(), 5);
+        assert_eq!(bits(&matrix.elem_lt(2.0).unwrap()), vec![true, true, false, false, false, false]);
+        assert_eq!(matrix.elem_le(2.0).unwrap().count_ones(), 3);
+        assert_eq!(matrix.elem_gt(2.0).unwrap().count_ones(), 3);
+        assert_eq!(matrix.elem_ge(2.0).unwrap().count_ones(), 4);
+        assert_eq!(bits(&matrix.between(1.0, 3.0).unwrap()), vec![false, true, true, true, false, false]);
+    }
+
+    #[test]
+    fn test_broadcast_comparisons() {
+        let matrix = matrix();
+        let thresholds = Matrix::from_shape_vec((1, 3), vec![0.0, 4.0, 4.0]).unwrap();
+        assert_eq!(bits(&matrix.elem_gt(&thresholds).unwrap()), vec![false, false, false, true, false, true]);
+
+        let column = matrix.as_lens().col(1).unwrap();
+        assert_eq!(bits(&matrix.elem_ge(&column).unwrap()), vec![false, true, true, false, true, true]);
+
+        let wide: Matrix<f64> = Matrix::zeros((2, 4)).unwrap();
+        assert!(matches!(
+            matrix.elem_eq(&wide),
+            Err(MatricalError::ShapeMismatch { operation: "eq", .. })
+        ));
+        // The right-hand side broadcasts to the left, never the other way round
+        assert!(thresholds.elem_lt(&matrix).is_err());
+    }
+
+    #[test]
+    fn test_approx_eq_and_nan() {
+        let values = Matrix::from_shape_vec((1, 4), vec![1.0, 1.0 + 1e-10, 100.0, f64::NAN]).unwrap();
+        let target = Matrix::from_shape_vec((1, 4), vec![1.0, 1.0, 101.0, f64::NAN]).unwrap();
+        assert_eq!(bits(&values.approx_eq(&target, 1e-9, 0.0).unwrap()), vec![true, true, false, false]);
+        assert_eq!(bits(&values.approx_eq(&target, 0.0, 0.01).unwrap()), vec![true, true, true, false]);
+        assert!(values.elem_ne(f64::NAN).unwrap().iter().all(|bit| bit));
+        assert_eq!(values.elem_eq(f64::NAN).unwrap().count_ones(), 0);
+    }
+
+    #[test]
+    fn test_masks_plug_into_selection_and_validity() {
+        let matrix = matrix();
+        let lens = matrix.lens(Region::new(Index::new(0, 1), Index::new(2, 3)).unwrap()).unwrap();
+        let large = lens.elem_ge(2.0).unwrap();
+        let selection = lens.masked(&large).unwrap();
+        assert_eq!(selection.to_vec(), vec![2.0, 4.0, 5.0]);
+
+        let mut masked = MaskedMatrix::all_valid(matrix.clone());
+        masked.invalidate_where(&matrix.elem_gt(3.0).unwrap()).unwrap();
+        assert_eq!(masked.count_valid(), 4);
+        assert_eq!(masked.get((1, 1)).unwrap(), None);
+        assert!(masked.invalidate_where(&large).is_err());
+
+        let valid = MaskedMatrix::new(matrix.clone(), matrix.elem_ne(0.0).unwrap()).unwrap();
+        assert_eq!(valid.get((0, 0)).unwrap(), None);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::error::MatricalError;
+use crate::operations::mechanics::{Validation, ValidationFailure, ValidationMode};
+use crate::schematics::mask::Mask;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Index, Region};
+use crate::strategies::lens::Lens;
+
+use serde::{Deserialize, Serialize};
+
+// Cell validation
+//
+// A Validation checks one value at a time; these apply it to every cell of a
+// Lens, a Region of a Matrix, or to the columns named by a list of
+// ColumnSchemas, so incoming data can be checked against per-feature ranges
+// before any Gear reads it. The result is a CellReport: a Mask over the
+// checked cells, true where a cell passed, and the failures of every cell that
+// did not. Every strategy runs on every cell, so a failing cell lists all the
+// strategies it failed.
+//
+
+// The ColumnSchema struct, the Validation every value of one column must pass
+pub struct ColumnSchema<V: 'static> {
+    col: usize,
+    validation: Validation<V>,
+}
+
+impl<V: 'static> ColumnSchema<V> {
+    // Create a new ColumnSchema
+    pub fn new(col: usize, validation: Validation<V>) -> Self {
+        Self { col, validation }
+    }
+
+    // Get the column the schema applies to
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    // Get the Validation the column must pass
+    pub fn validation(&self) -> &Validation<V> {
+        &self.validation
+    }
+}
+
+// A failing cell and the strategies it failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellFailure {
+    // The cell, in the coordinates of the owning Matrix
+    index: Index,
+    failures: Vec<ValidationFailure>,
+}
+
+impl CellFailure {
+    // Get the cell, in the coordinates of the owning Matrix
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    // Get the strategies the cell failed
+    pub fn failures(&self) -> &[ValidationFailure] {
+        &self.failures
+    }
+}
+
+// The CellReport struct
+//
+// The outcome of validating the cells of a Region. `valid` is a Mask over the
+// Region, indexed relative to its start, and `failures` lists the failing
+// cells in row-major order with their indices in the coordinates of the
+// owning Matrix.
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellReport {
+    region: Region,
+    valid: Mask,
+    failures: Vec<CellFailure>,
+}
+
+impl CellReport {
+    // Get the validated Region, in the coordinates of the owning Matrix
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    // Get the Mask of cells that passed
+    pub fn valid(&self) -> &Mask {
+        &self.valid
+    }
+
+    // Take the Mask of cells that passed
+    pub fn into_mask(self) -> Mask {
+        self.valid
+    }
+
+    // Get the failing cells
+    pub fn failures(&self) -> &[CellFailure] {
+        &self.failures
+    }
+
+    // Get the number of cells checked
+    pub fn checked(&self) -> usize {
+        self.valid.len()
+    }
+
+    // Check whether every cell passed
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    // Fail with the first failing cell, for callers that reject invalid input outright
+    pub fn check(&self) -> Result<(), MatricalError> {
+        match self.failures.first() {
+            Some(failure) => Err(MatricalError::InvalidValue {
+                operation: "validate",
+                index: Some(failure.index),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    // Record the failures of the cell at a Region-relative index
+    fn record(&mut self, index: Index, failures: Vec<ValidationFailure>) {
+        if failures.is_empty() {
+            return;
+        }
+        // The index is inside the Region, so neither can fail
+        let _ = self.valid.set(index, false);
+        let start = self.region.start();
+        self.failures.push(CellFailure {
+            index: Index::new(start.row() + index.row(), start.col() + index.col()),
+            failures,
+        });
+    }
+}
+
+// Validate one value, collecting every failure
+fn cell_failures<V: 'static>(validation: &Validation<V>, value: &V) -> Vec<ValidationFailure> {
+    let mut failures = Vec::new();
+    validation.collect_failures(value, ValidationMode::CollectAll, &mut failures);
+    failures
+}
+
+impl<V: 'static> Lens<'_, V> {
+    // Validate every cell of the Lens
+    pub fn validate(&self, validation: &Validation<V>) -> CellReport {
+        let mut report = CellReport {
+            region: self.region(),
+            valid: Mask::trues(self.shape()),
+            failures: Vec::new(),
+        };
+        for index in self.shape().region() {
+            if let Ok(value) = self.get(index) {
+                report.record(index, cell_failures(validation, value));
+            }
+        }
+        report
+    }
+
+    // Validate the columns named by `schemas`; columns without a schema pass
+    pub fn validate_columns(&self, schemas: &[ColumnSchema<V>]) -> Result<CellReport, MatricalError> {
+        if let Some(schema) = schemas.iter().find(|schema| schema.col >= self.cols()) {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(0, schema.col),
+                shape: self.shape(),
+            });
+        }
+        let mut report = CellReport {
+            region: self.region(),
+            valid: Mask::trues(self.shape()),
+            failures: Vec::new(),
+        };
+        // Walk row-major so the failures come out in the same order as `validate`
+        for row in 0..self.rows() {
+            for col in 0..self.cols() {
+                let value = self.get((row, col))?;
+                let failures = schemas
+                    .iter()
+                    .filter(|schema| schema.col == col)
+                    .flat_map(|schema| cell_failures(&schema.validation, value))
+                    .collect();
+                report.record(Index::new(row, col), failures);
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl<V: 'static> Matrix<V> {
+    // Validate every cell of a Region of the Matrix
+    pub fn validate(&self, validation: &Validation<V>, region: Region) -> Result<CellReport, MatricalError> {
+        Ok(self.lens(region)?.validate(validation))
+    }
+
+    // Validate the columns named by `schemas`; columns without a schema pass
+    pub fn validate_columns(&self, schemas: &[ColumnSchema<V>]) -> Result<CellReport, MatricalError> {
+        self.as_lens().validate_columns(schemas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::mechanics::{Between, GreaterThanOrEqual, LessThan, PathSegment};
+
+    fn matrix() -> Matrix<f64> {
+        Matrix::from_shape_vec((3, 3), vec![0.5, 10.0, -1.0, 1.5, 20.0, 0.0, 0.2, -5.0, 3.0]).unwrap()
+    }
+
+    fn unit() -> Validation<f64> {
+        let mut validation = Validation::new();
+        validation.add_strategy(Between::new(0.0, 1.0));
+        validation
+    }
+
+    #[test]
+    fn test_validate_region() {
+        let matrix = matrix();
+        let region = Region::new(Index::new(0, 0), Index::new(3, 1)).unwrap();
+        let report = matrix.validate(&unit(), region).unwrap();
+        assert_eq!(report.checked(), 3);
+        assert_eq!(report.valid().iter().collect::<Vec<_>>(), vec![true, false, true]);
+        assert_eq!(report.failures().len(), 1);
+        assert_eq!(report.failures()[0].index(), Index::new(1, 0));
+        assert_eq!(report.failures()[0].failures()[0].path(), &[PathSegment::Strategy(0)]);
+
+        // Failing cells are reported in the coordinates of the Matrix
+        let region = Region::new(Index::new(1, 1), Index::new(3, 3)).unwrap();
+        let report = matrix.validate(&unit(), region).unwrap();
+        assert_eq!(report.valid().iter().collect::<Vec<_>>(), vec![false, true, false, false]);
+        let indices: Vec<_> = report.failures().iter().map(|failure| failure.index()).collect();
+        assert_eq!(indices, vec![Index::new(1, 1), Index::new(2, 1), Index::new(2, 2)]);
+        assert!(matches!(
+            report.check(),
+            Err(MatricalError::InvalidValue { index: Some(index), .. }) if index == Index::new(1, 1)
+        ));
+
+        let outside = Region::new(Index::new(0, 0), Index::new(4, 1)).unwrap();
+        assert!(matrix.validate(&unit(), outside).is_err());
+    }
+
+    #[test]
+    fn test_validate_columns() {
+        let matrix = matrix();
+        let mut magnitude = Validation::new();
+        magnitude.add_strategy(GreaterThanOrEqual::new(0.0));
+        magnitude.add_strategy(LessThan::new(15.0));
+        let schemas = vec![ColumnSchema::new(0, unit()), ColumnSchema::new(1, magnitude)];
+
+        let report = matrix.validate_columns(&schemas).unwrap();
+        assert_eq!(report.checked(), 9);
+        assert_eq!(report.valid().count_ones(), 6);
+        let indices: Vec<_> = report.failures().iter().map(|failure| failure.index()).collect();
+        assert_eq!(indices, vec![Index::new(1, 0), Index::new(1, 1), Index::new(2, 1)]);
+        assert_eq!(report.failures()[1].failures()[0].strategy(), "less_than");
+
+        let last = matrix.validate_columns(&[ColumnSchema::new(2, unit())]).unwrap();
+        assert_eq!(last.failures().len(), 2);
+        assert!(matrix.validate_columns(&[]).unwrap().is_valid());
+        assert!(matches!(
+            matrix.validate_columns(&[ColumnSchema::new(3, unit())]),
+            Err(MatricalError::IndexOutOfBounds { .. })
+        ));
+    }
+}
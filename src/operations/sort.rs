@@ -1,58 +1,273 @@
+use crate::error::MatricalError;
+use crate::schematics::mask::Mask;
+use crate::schematics::masked::MaskedMatrix;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Index, Shape};
+use crate::strategies::lens::Lens;
+use crate::strategies::validity::MaskedLens;
 
-use dashmap::DashMap as HashMap;
+use std::cmp::Ordering;
 
+// Sorting
+//
+// Rows are sorted by the values in a list of key columns, and columns by the
+// values in a list of key rows: the first key decides, later keys break its
+// ties, and rows or columns that tie on every key keep their original order.
+// A sort either returns the permutation, where entry `i` is the original
+// position of what ends up at position `i`, or builds a permuted Matrix
+// whose row and column Tags travel with their rows and columns. NaN keys,
+// and in a MaskedLens invalid keys, have no order; they are grouped at the
+// end or the start according to the SortOrder, whatever the direction.
+//
 
-use crate::schematics::data::*;
-use crate::schematics::element::*;
-use crate::schematics::matrix::*;
+// The direction of a sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Ascending,
+    Descending,
+}
 
+// Where a sort places keys that have no order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPlacement {
+    First,
+    #[default]
+    Last,
+}
 
+// How keys are ordered: ascending with missing keys last by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortOrder {
+    direction: Direction,
+    nans: NanPlacement,
+}
 
+impl SortOrder {
+    // Create a new ascending SortOrder
+    pub fn ascending() -> Self {
+        Self::default()
+    }
 
+    // Create a new descending SortOrder
+    pub fn descending() -> Self {
+        Self {
+            direction: Direction::Descending,
+            ..Self::default()
+        }
+    }
 
-// Defines a set of methods that can be used to sort a given data set.
-// //
-// trait SortStrategy: Strategy {
-//     fn sort(&self, data: &HashMap<String, String>) -> Result<(), String>;
-// }
+    // Set where keys without an order are placed
+    pub fn nans(mut self, nans: NanPlacement) -> Self {
+        self.nans = nans;
+        self
+    }
 
+    // Compare two keys, `None` marking a key that is missing
+    fn compare<V: PartialOrd>(&self, lhs: Option<&V>, rhs: Option<&V>) -> Ordering {
+        let ordered = |value: &&V| value.partial_cmp(value).is_some();
+        let missing = match self.nans {
+            NanPlacement::First => Ordering::Less,
+            NanPlacement::Last => Ordering::Greater,
+        };
+        match (lhs.filter(ordered), rhs.filter(ordered)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => missing,
+            (Some(_), None) => missing.reverse(),
+            (Some(lhs), Some(rhs)) => {
+                let ordering = lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal);
+                match self.direction {
+                    Direction::Ascending => ordering,
+                    Direction::Descending => ordering.reverse(),
+                }
+            }
+        }
+    }
+}
 
-// // Defines a set of methods that can be used to sort a given data set.
-// //
-// struct SortStrategyImpl {
-//     data: HashMap<String, String>,
-// }
+// Stably sort `len` lanes by the values `key(lane, key)` takes at each of `keys`
+fn permutation<'v, V, F>(len: usize, keys: &[usize], order: SortOrder, key: F) -> Vec<usize>
+where
+    V: PartialOrd + 'v,
+    F: Fn(usize, usize) -> Option<&'v V>,
+{
+    let mut permutation: Vec<usize> = (0..len).collect();
+    permutation.sort_by(|&lhs, &rhs| {
+        keys.iter()
+            .map(|&k| order.compare(key(lhs, k), key(rhs, k)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    permutation
+}
 
-// impl SortStrategyImpl {
-//     fn new() -> Self {
-//         Self {
-//             data: HashMap::new(),
-//         }
-//     }
-// }
+fn check_key_cols(keys: &[usize], shape: Shape) -> Result<(), MatricalError> {
+    match keys.iter().find(|&&col| col >= shape.cols()) {
+        Some(&col) => Err(MatricalError::IndexOutOfBounds {
+            index: Index::new(0, col),
+            shape,
+        }),
+        None => Ok(()),
+    }
+}
 
-// impl Strategy for SortStrategyImpl {
-//     fn prepare(&self, data: &HashMap<String, String>) -> Result<(), String> {
-//         Ok(())
-//     }
+fn check_key_rows(keys: &[usize], shape: Shape) -> Result<(), MatricalError> {
+    match keys.iter().find(|&&row| row >= shape.rows()) {
+        Some(&row) => Err(MatricalError::IndexOutOfBounds {
+            index: Index::new(row, 0),
+            shape,
+        }),
+        None => Ok(()),
+    }
+}
 
-//     fn execute(&self, data: &HashMap<String, String>) -> Result<(), String> {
-//         Ok(())
-//     }
+impl<V: PartialOrd> Lens<'_, V> {
+    // Get the permutation sorting the rows by the values in the key columns
+    pub fn sort_rows_by(&self, keys: &[usize], order: SortOrder) -> Result<Vec<usize>, MatricalError> {
+        check_key_cols(keys, self.shape())?;
+        Ok(permutation(self.rows(), keys, order, |row, col| self.get((row, col)).ok()))
+    }
 
-//     fn result(&self) -> Result<(), String> {
-//         Ok(())
-//     }
-// }
+    // Get the permutation sorting the columns by the values in the key rows
+    pub fn sort_cols_by(&self, keys: &[usize], order: SortOrder) -> Result<Vec<usize>, MatricalError> {
+        check_key_rows(keys, self.shape())?;
+        Ok(permutation(self.cols(), keys, order, |col, row| self.get((row, col)).ok()))
+    }
 
-// impl SortStrategy for SortStrategyImpl {
-//     fn sort(&self, data: &HashMap<String, String>) -> Result<(), String> {
-//         Ok(())
-//     }
-// }
+    // Copy the Lens with its rows sorted by the values in the key columns
+    pub fn sorted_rows_by(&self, keys: &[usize], order: SortOrder) -> Result<Matrix<V>, MatricalError>
+    where
+        V: Clone,
+    {
+        let permutation = self.sort_rows_by(keys, order)?;
+        Ok(self.select_rows(&permutation)?.to_matrix())
+    }
 
-// // Defines a set of methods that can be used to sort a given data set.
-//
-// struct SortStrategyImpl2 {
-//     data: HashMap<String, String>,
-// }
\ No newline at end of file
+    // Copy the Lens with its columns sorted by the values in the key rows
+    pub fn sorted_cols_by(&self, keys: &[usize], order: SortOrder) -> Result<Matrix<V>, MatricalError>
+    where
+        V: Clone,
+    {
+        let permutation = self.sort_cols_by(keys, order)?;
+        Ok(self.select_cols(&permutation)?.to_matrix())
+    }
+}
+
+impl<V: PartialOrd> Matrix<V> {
+    // Get the permutation sorting the rows by the values in the key columns
+    pub fn sort_rows_by(&self, keys: &[usize], order: SortOrder) -> Result<Vec<usize>, MatricalError> {
+        self.as_lens().sort_rows_by(keys, order)
+    }
+
+    // Get the permutation sorting the columns by the values in the key rows
+    pub fn sort_cols_by(&self, keys: &[usize], order: SortOrder) -> Result<Vec<usize>, MatricalError> {
+        self.as_lens().sort_cols_by(keys, order)
+    }
+
+    // Copy the Matrix with its rows sorted by the values in the key columns
+    pub fn sorted_rows_by(&self, keys: &[usize], order: SortOrder) -> Result<Matrix<V>, MatricalError>
+    where
+        V: Clone,
+    {
+        self.as_lens().sorted_rows_by(keys, order)
+    }
+
+    // Copy the Matrix with its columns sorted by the values in the key rows
+    pub fn sorted_cols_by(&self, keys: &[usize], order: SortOrder) -> Result<Matrix<V>, MatricalError>
+    where
+        V: Clone,
+    {
+        self.as_lens().sorted_cols_by(keys, order)
+    }
+}
+
+impl<V: PartialOrd> MaskedLens<'_, V> {
+    // Get the permutation sorting the rows by the valid values in the key columns
+    pub fn sort_rows_by(&self, keys: &[usize], order: SortOrder) -> Result<Vec<usize>, MatricalError> {
+        check_key_cols(keys, self.shape())?;
+        Ok(permutation(self.shape().rows(), keys, order, |row, col| self.get((row, col)).ok().flatten()))
+    }
+
+    // Get the permutation sorting the columns by the valid values in the key rows
+    pub fn sort_cols_by(&self, keys: &[usize], order: SortOrder) -> Result<Vec<usize>, MatricalError> {
+        check_key_rows(keys, self.shape())?;
+        Ok(permutation(self.shape().cols(), keys, order, |col, row| self.get((row, col)).ok().flatten()))
+    }
+}
+
+impl<V: PartialOrd + Clone> MaskedMatrix<V> {
+    // Copy the MaskedMatrix with its rows sorted by the valid values in the key columns
+    pub fn sorted_rows_by(&self, keys: &[usize], order: SortOrder) -> Result<MaskedMatrix<V>, MatricalError> {
+        let permutation = self.as_lens().sort_rows_by(keys, order)?;
+        let values = self.values().as_lens().select_rows(&permutation)?.to_matrix();
+        let valid = Mask::from_fn(self.shape(), |row, col| {
+            self.valid().get((permutation[row], col)).unwrap_or(false)
+        });
+        MaskedMatrix::new(values, valid)
+    }
+
+    // Copy the MaskedMatrix with its columns sorted by the valid values in the key rows
+    pub fn sorted_cols_by(&self, keys: &[usize], order: SortOrder) -> Result<MaskedMatrix<V>, MatricalError> {
+        let permutation = self.as_lens().sort_cols_by(keys, order)?;
+        let values = self.values().as_lens().select_cols(&permutation)?.to_matrix();
+        let valid = Mask::from_fn(self.shape(), |row, col| {
+            self.valid().get((row, permutation[col])).unwrap_or(false)
+        });
+        MaskedMatrix::new(values, valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::tag::Tag;
+
+    fn matrix() -> Matrix<f64> {
+        Matrix::from_shape_vec((4, 2), vec![2.0, 1.0, 1.0, 5.0, 2.0, 0.0, f64::NAN, 3.0]).unwrap()
+    }
+
+    #[test]
+    fn test_multi_key_stable_sort() {
+        let matrix = matrix();
+        assert_eq!(matrix.sort_rows_by(&[0], SortOrder::ascending()).unwrap(), vec![1, 0, 2, 3]);
+        assert_eq!(matrix.sort_rows_by(&[0, 1], SortOrder::ascending()).unwrap(), vec![1, 2, 0, 3]);
+        assert_eq!(matrix.sort_rows_by(&[0], SortOrder::descending()).unwrap(), vec![0, 2, 1, 3]);
+        assert_eq!(matrix.sort_rows_by(&[], SortOrder::ascending()).unwrap(), vec![0, 1, 2, 3]);
+        assert!(matches!(
+            matrix.sort_rows_by(&[2], SortOrder::ascending()),
+            Err(MatricalError::IndexOutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_nan_placement() {
+        let matrix = matrix();
+        let first = SortOrder::descending().nans(NanPlacement::First);
+        assert_eq!(matrix.sort_rows_by(&[0], first).unwrap(), vec![3, 0, 2, 1]);
+
+        let sorted = matrix.sorted_cols_by(&[3], SortOrder::ascending()).unwrap();
+        assert_eq!(sorted.get((0, 0)).unwrap(), &1.0);
+        assert!(sorted.get((3, 1)).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_tags_travel_with_rows() {
+        let mut matrix = matrix();
+        let tag = Tag::custom("sensor", "b").unwrap();
+        matrix.row_tags_mut(1).unwrap().insert(tag.clone()).unwrap();
+        let sorted = matrix.sorted_rows_by(&[1], SortOrder::ascending()).unwrap();
+        assert_eq!(sorted.get((1, 0)).unwrap(), &2.0);
+        assert_eq!(sorted.get((3, 1)).unwrap(), &5.0);
+        assert!(sorted.row_tags(3).unwrap().contains(&tag));
+        assert!(!sorted.row_tags(1).unwrap().contains(&tag));
+    }
+
+    #[test]
+    fn test_invalid_keys_sort_as_missing() {
+        let masked = MaskedMatrix::from_options((3, 1), vec![Some(3.0), None, Some(1.0)]).unwrap();
+        assert_eq!(masked.as_lens().sort_rows_by(&[0], SortOrder::ascending()).unwrap(), vec![2, 0, 1]);
+
+        let sorted = masked.sorted_rows_by(&[0], SortOrder::ascending().nans(NanPlacement::First)).unwrap();
+        assert_eq!(sorted.iter().collect::<Vec<_>>(), vec![None, Some(&1.0), Some(&3.0)]);
+    }
+}
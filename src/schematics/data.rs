@@ -1,6 +1,4 @@
-
-
-
+use crate::error::MatricalError;
 
 pub trait SqlContextTrait {
     fn is_valid(&self) -> bool;
@@ -34,13 +32,8 @@ impl<T> IsValid for T {
     }
 }
 
-pub enum SqlError {
-    InvalidValue,
-    InvalidContext,
-}
-
 pub trait SqlValidationStrategy<T> {
-    fn is_valid(&self, value: &T) -> Result<String, SqlError>;
+    fn is_valid(&self, value: &T) -> Result<String, MatricalError>;
 }
 
 pub struct IsValidStrategy<T> {
@@ -59,14 +52,17 @@ impl<T> IsValidStrategy<T> {
 }
 
 impl<T> SqlValidationStrategy<T> for IsValidStrategy<T> {
-    fn is_valid(&self, value: &T) -> Result<String, SqlError> {
+    fn is_valid(&self, value: &T) -> Result<String, MatricalError> {
         // If the value is valid, return Ok
         if (self.validator)(value) {
             Ok(String::from(""))
         }
         // Otherwise, return an error
         else {
-            Err(SqlError::InvalidValue)
+            Err(MatricalError::InvalidValue {
+                operation: "is_valid",
+                index: None,
+            })
         }
     }
 }
@@ -87,14 +83,14 @@ impl<T> SqlContextStrategy<T> {
 }
 
 impl<T> SqlValidationStrategy<SqlContext<T>> for SqlContextStrategy<T> {
-    fn is_valid(&self, context: &SqlContext<T>) -> Result<String, SqlError> {
+    fn is_valid(&self, context: &SqlContext<T>) -> Result<String, MatricalError> {
         // If the context is valid, return Ok
         if (self.validator)(context) {
             Ok(String::from(""))
         }
         // Otherwise, return an error
         else {
-            Err(SqlError::InvalidContext)
+            Err(MatricalError::InvalidContext { operation: "is_valid" })
         }
     }
 }
@@ -115,7 +111,7 @@ impl<T> SqlValidation<T> {
         self.strategies.push(Box::new(strategy));
     }
 
-    pub fn is_valid(&self, value: &T) -> Result<String, SqlError> {
+    pub fn is_valid(&self, value: &T) -> Result<String, MatricalError> {
         let mut sql = String::new();
         for strategy in &self.strategies {
             // let result = self.validate_strategy(strategy, value);
@@ -151,7 +147,7 @@ impl<T> SqlValidationBuilder<T> {
     // }
 }
 
-// impl<T> From<T> for Result<String, SqlError> {
+// impl<T> From<T> for Result<String, MatricalError> {
 //     fn from(value: T) -> Self {
 //         let mut validation = SqlValidation::new();
 //         validation.add_strategy(IsValidStrategy::new(IsValid::is_valid));
@@ -160,7 +156,7 @@ impl<T> SqlValidationBuilder<T> {
 //     }
 // }
 
-// fn validate_sql<T>(sql: &SqlContext<T>) -> Result<String, SqlError> {
+// fn validate_sql<T>(sql: &SqlContext<T>) -> Result<String, MatricalError> {
 //     let mut builder = SqlValidationBuilder::new();
 //     builder.add_strategy(IsValidStrategy::new(IsValid::is_valid));
 //     builder.add_strategy(SqlContextStrategy::new(SqlContextTrait::is_valid));
@@ -0,0 +1,204 @@
+use crate::error::MatricalError;
+use crate::schematics::shape::{Index, Shape};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// Struct to hold a two-dimensional boolean mask
+//
+// Cells are packed row-major into 64-bit words, one bit per cell, so a mask
+// over a large Matrix costs an eighth of a `Vec<bool>`. Bits past the last
+// cell of the final word are always zero.
+//
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mask {
+    shape: Shape,
+    words: Vec<u64>,
+}
+
+impl Mask {
+    // Create a new Mask with every cell set to `value`
+    pub fn filled(shape: Shape, value: bool) -> Self {
+        let fill = if value { u64::MAX } else { 0 };
+        let mut mask = Self {
+            shape,
+            words: vec![fill; shape.len().div_ceil(WORD_BITS)],
+        };
+        mask.clear_padding();
+        mask
+    }
+
+    // Create a new Mask with every cell cleared
+    pub fn falses(shape: Shape) -> Self {
+        Self::filled(shape, false)
+    }
+
+    // Create a new Mask with every cell set
+    pub fn trues(shape: Shape) -> Self {
+        Self::filled(shape, true)
+    }
+
+    // Create a new Mask by calling `f(row, col)` for every cell in row-major order
+    pub fn from_fn<F>(shape: Shape, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> bool,
+    {
+        let mut mask = Self::falses(shape);
+        for (offset, index) in shape.region().iter().enumerate() {
+            if f(index.row(), index.col()) {
+                mask.words[offset / WORD_BITS] |= 1 << (offset % WORD_BITS);
+            }
+        }
+        mask
+    }
+
+    // Create a new Mask from row-major values
+    pub fn from_vec(shape: Shape, values: Vec<bool>) -> Result<Self, MatricalError> {
+        if values.len() != shape.len() {
+            return Err(MatricalError::LengthMismatch {
+                expected: shape.len(),
+                actual: values.len(),
+            });
+        }
+        let cols = shape.cols();
+        Ok(Self::from_fn(shape, |row, col| values[row * cols + col]))
+    }
+
+    // Create a new Mask from packed row-major words, zeroing any padding bits
+    pub(crate) fn from_words(shape: Shape, words: Vec<u64>) -> Self {
+        debug_assert_eq!(words.len(), shape.len().div_ceil(WORD_BITS));
+        let mut mask = Self { shape, words };
+        mask.clear_padding();
+        mask
+    }
+
+    // Get the shape of the Mask
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    // Get the number of cells
+    pub fn len(&self) -> usize {
+        self.shape.len()
+    }
+
+    // Check whether the Mask has no cells
+    pub fn is_empty(&self) -> bool {
+        self.shape.is_empty()
+    }
+
+    // Get the value of a cell
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<bool, MatricalError> {
+        let offset = self.offset(index.into())?;
+        Ok(self.bit(offset))
+    }
+
+    // Set the value of a cell, returning the previous value
+    pub fn set<I: Into<Index>>(&mut self, index: I, value: bool) -> Result<bool, MatricalError> {
+        let offset = self.offset(index.into())?;
+        let previous = self.bit(offset);
+        let bit = 1 << (offset % WORD_BITS);
+        if value {
+            self.words[offset / WORD_BITS] |= bit;
+        } else {
+            self.words[offset / WORD_BITS] &= !bit;
+        }
+        Ok(previous)
+    }
+
+    // Get the number of set cells
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    // Iterate over every cell in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len()).map(move |offset| self.bit(offset))
+    }
+
+    // Iterate over the indices of the set cells in row-major order
+    pub fn iter_ones(&self) -> impl Iterator<Item = Index> + '_ {
+        let cols = self.shape.cols();
+        self.words.iter().enumerate().flat_map(move |(position, &word)| {
+            let mut remaining = word;
+            std::iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+                let bit = remaining.trailing_zeros() as usize;
+                remaining &= remaining - 1;
+                let offset = position * WORD_BITS + bit;
+                Some(Index::new(offset / cols, offset % cols))
+            })
+        })
+    }
+
+    // Get the packed words backing the Mask
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    fn offset(&self, index: Index) -> Result<usize, MatricalError> {
+        let index = self.shape.check_index(index)?;
+        Ok(index.row() * self.shape.cols() + index.col())
+    }
+
+    fn bit(&self, offset: usize) -> bool {
+        self.words[offset / WORD_BITS] >> (offset % WORD_BITS) & 1 == 1
+    }
+
+    // Zero the unused bits of the final word so counts and comparisons stay exact
+    fn clear_padding(&mut self) {
+        let used = self.len() % WORD_BITS;
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(rows: usize, cols: usize) -> Shape {
+        Shape::new(rows, cols).unwrap()
+    }
+
+    #[test]
+    fn test_filled_clears_padding() {
+        let mask = Mask::trues(shape(3, 25));
+        assert_eq!(mask.as_words().len(), 2);
+        assert_eq!(mask.count_ones(), 75);
+        assert_eq!(Mask::falses(shape(3, 25)).count_ones(), 0);
+        assert!(Mask::trues(shape(0, 4)).is_empty());
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut mask = Mask::falses(shape(2, 40));
+        assert!(!mask.set((1, 30), true).unwrap());
+        assert!(mask.get((1, 30)).unwrap());
+        assert!(!mask.get((0, 30)).unwrap());
+        assert!(mask.set((1, 30), false).unwrap());
+        assert_eq!(mask.count_ones(), 0);
+        assert!(matches!(mask.get((2, 0)), Err(MatricalError::IndexOutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let mask = Mask::from_vec(shape(2, 2), vec![true, false, false, true]).unwrap();
+        assert_eq!(mask.iter().collect::<Vec<_>>(), vec![true, false, false, true]);
+        assert!(matches!(
+            Mask::from_vec(shape(2, 2), vec![true]),
+            Err(MatricalError::LengthMismatch { expected: 4, actual: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_iter_ones_spans_words() {
+        let mask = Mask::from_fn(shape(10, 10), |row, col| row == col);
+        let ones: Vec<(usize, usize)> = mask.iter_ones().map(Into::into).collect();
+        assert_eq!(ones, (0..10).map(|i| (i, i)).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::MatricalError;
+use crate::schematics::mask::Mask;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Index, Shape};
+
+// Struct pairing a Matrix with a validity Mask
+//
+// A set bit marks a cell holding a captured measurement; a cleared bit marks
+// one that was not captured. The value stored under a cleared bit is only a
+// placeholder: reads return `None` for it and masked Gears never see it, so a
+// missing measurement cannot pass for zero or be confused with a computed NaN.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskedMatrix<V> {
+    values: Matrix<V>,
+    valid: Mask,
+}
+
+impl<V> MaskedMatrix<V> {
+    // Create a new MaskedMatrix, rejecting a Mask of a different shape
+    pub fn new(values: Matrix<V>, valid: Mask) -> Result<Self, MatricalError> {
+        if values.shape() != valid.shape() {
+            return Err(MatricalError::ShapeMismatch {
+                operation: "masked matrix",
+                expected: values.shape(),
+                actual: valid.shape(),
+            });
+        }
+        Ok(Self { values, valid })
+    }
+
+    // Create a new MaskedMatrix in which every cell is valid
+    pub fn all_valid(values: Matrix<V>) -> Self {
+        let valid = Mask::trues(values.shape());
+        Self { values, valid }
+    }
+
+    // Create a new MaskedMatrix from row-major values, `None` marking cells that were not captured
+    pub fn from_options(shape: (usize, usize), values: Vec<Option<V>>) -> Result<Self, MatricalError>
+    where
+        V: Default,
+    {
+        let shape = Shape::try_from(shape)?;
+        let valid = Mask::from_vec(shape, values.iter().map(Option::is_some).collect())?;
+        let values = values.into_iter().map(Option::unwrap_or_default).collect();
+        let values = Matrix::from_shape_vec((shape.rows(), shape.cols()), values)?;
+        Ok(Self { values, valid })
+    }
+
+    // Get the shape
+    pub fn shape(&self) -> Shape {
+        self.values.shape()
+    }
+
+    // Get the values, including placeholders under invalid cells
+    pub fn values(&self) -> &Matrix<V> {
+        &self.values
+    }
+
+    // Get the validity Mask
+    pub fn valid(&self) -> &Mask {
+        &self.valid
+    }
+
+    // Check whether a cell is valid
+    pub fn is_valid<I: Into<Index>>(&self, index: I) -> Result<bool, MatricalError> {
+        self.valid.get(index)
+    }
+
+    // Get the value of a cell, or `None` if it is invalid
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<Option<&V>, MatricalError> {
+        let index = index.into();
+        let value = self.values.get(index)?;
+        Ok(self.valid.get(index)?.then_some(value))
+    }
+
+    // Set the value of a cell and mark it valid
+    pub fn set<I: Into<Index>>(&mut self, index: I, value: V) -> Result<(), MatricalError> {
+        let index = index.into();
+        *self.values.get_mut(index)? = value;
+        self.valid.set(index, true)?;
+        Ok(())
+    }
+
+    // Mark a cell invalid
+    pub fn invalidate<I: Into<Index>>(&mut self, index: I) -> Result<(), MatricalError> {
+        self.valid.set(index, false)?;
+        Ok(())
+    }
+
+    // Get the number of valid cells
+    pub fn count_valid(&self) -> usize {
+        self.valid.count_ones()
+    }
+
+    // Iterate over the cells in row-major order, `None` for invalid cells
+    pub fn iter(&self) -> impl Iterator<Item = Option<&V>> {
+        self.values
+            .iter()
+            .zip(self.valid.iter())
+            .map(|(value, valid)| valid.then_some(value))
+    }
+
+    // Mark a whole column invalid, for a feature that cannot be captured in this mode
+    pub fn mask_col(&mut self, col: usize) -> Result<(), MatricalError> {
+        self.mask_cols(&[col])
+    }
+
+    // Mark whole columns invalid; nothing is changed unless every column is in bounds
+    pub fn mask_cols(&mut self, cols: &[usize]) -> Result<(), MatricalError> {
+        let shape = self.shape();
+        for &col in cols {
+            if col >= shape.cols() {
+                return Err(MatricalError::IndexOutOfBounds {
+                    index: Index::new(0, col),
+                    shape,
+                });
+            }
+        }
+        for &col in cols {
+            for row in 0..shape.rows() {
+                self.valid.set((row, col), false)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Mark a whole row invalid
+    pub fn mask_row(&mut self, row: usize) -> Result<(), MatricalError> {
+        let shape = self.shape();
+        if row >= shape.rows() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(row, 0),
+                shape,
+            });
+        }
+        for col in 0..shape.cols() {
+            self.valid.set((row, col), false)?;
+        }
+        Ok(())
+    }
+
+    // Check whether a column holds no valid cells
+    pub fn is_col_masked(&self, col: usize) -> Result<bool, MatricalError> {
+        let shape = self.shape();
+        if col >= shape.cols() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(0, col),
+                shape,
+            });
+        }
+        for row in 0..shape.rows() {
+            if self.valid.get((row, col))? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Consume the MaskedMatrix and return its values and validity Mask
+    pub fn into_parts(self) -> (Matrix<V>, Mask) {
+        (self.values, self.valid)
+    }
+
+    // Borrow the values mutably alongside the Mask
+    pub(crate) fn parts_mut(&mut self) -> (&mut Matrix<V>, &mut Mask) {
+        (&mut self.values, &mut self.valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_options() {
+        let matrix = MaskedMatrix::from_options((2, 2), vec![Some(1.0), None, Some(3.0), Some(4.0)]).unwrap();
+        assert_eq!(matrix.count_valid(), 3);
+        assert_eq!(matrix.get((0, 1)).unwrap(), None);
+        assert_eq!(matrix.get((1, 0)).unwrap(), Some(&3.0));
+        assert_eq!(matrix.iter().filter(Option::is_none).count(), 1);
+        assert!(MaskedMatrix::<f64>::from_options((2, 2), vec![None]).is_err());
+    }
+
+    #[test]
+    fn test_shapes_must_agree() {
+        let values: Matrix<f64> = Matrix::zeros((2, 3)).unwrap();
+        let valid = Mask::trues(Shape::new(3, 2).unwrap());
+        assert!(matches!(
+            MaskedMatrix::new(values, valid),
+            Err(MatricalError::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_set_and_invalidate() {
+        let mut matrix = MaskedMatrix::from_options((1, 2), vec![None, Some(2)]).unwrap();
+        matrix.set((0, 0), 7).unwrap();
+        matrix.invalidate((0, 1)).unwrap();
+        assert_eq!(matrix.iter().collect::<Vec<_>>(), vec![Some(&7), None]);
+        assert!(matrix.set((1, 0), 1).is_err());
+    }
+
+    #[test]
+    fn test_capability_masking() {
+        let mut matrix = MaskedMatrix::all_valid(Matrix::from_fn((3, 3), |row, col| row * 3 + col).unwrap());
+        matrix.mask_cols(&[2]).unwrap();
+        assert!(matrix.is_col_masked(2).unwrap());
+        assert!(!matrix.is_col_masked(1).unwrap());
+        assert_eq!(matrix.count_valid(), 6);
+
+        assert!(matrix.mask_cols(&[0, 3]).is_err());
+        assert!(!matrix.is_col_masked(0).unwrap());
+
+        matrix.mask_row(0).unwrap();
+        assert_eq!(matrix.count_valid(), 4);
+    }
+}
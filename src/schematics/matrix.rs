@@ -1,79 +1,321 @@
+use crate::error::MatricalError;
+use crate::schematics::shape::{Index, Shape};
+use crate::strategies::tag::TagSet;
 
+use ndarray::Array2;
+use num_traits::Zero;
 
-// use crate::*;
-// use crate::strategies::Tag;
-// use crate::strategies::ElementStrategy;
-// use crate::strategies::MatrixStrategy;
-// use crate::schematics::Element;
-// use crate::Element;
-// use crate::schematics::Matrix;
-// use crate::schematics::MatrixContext;
-// use crate::LensContext;
-// use crate::LensStrategy;
-// use crate::LensCalibration;
-// use crate::Calibration;
-
+// Struct to hold a dense two-dimensional matrix
+//
+// Elements are stored row-major in an `ndarray::Array2`, so the shape of the
+// Matrix is always the shape of its storage. Every constructor and accessor
+// checks its input and returns an error instead of panicking.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<V> {
+    data: Array2<V>,
+    context: MatrixContext,
+}
 
+// Metadata carried alongside the Matrix storage
+//
+// Tags can be attached to the whole Matrix and to each row and column. Row
+// and column Tags are kept in step with the shape of the storage, so every
+// row and column always has a (possibly empty) TagSet.
+//
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatrixContext {
+    tags: TagSet,
+    row_tags: Vec<TagSet>,
+    col_tags: Vec<TagSet>,
+}
 
-use crate::{Element, Tag};
+impl MatrixContext {
+    // Create a new MatrixContext with no Tags for a Matrix of the given shape
+    pub(crate) fn new(shape: Shape) -> Self {
+        Self {
+            tags: TagSet::new(),
+            row_tags: vec![TagSet::new(); shape.rows()],
+            col_tags: vec![TagSet::new(); shape.cols()],
+        }
+    }
 
+    // Get the Tags applied to the whole Matrix
+    pub fn tags(&self) -> &TagSet {
+        &self.tags
+    }
 
-use std::any::Any;
-use std::collections::HashMap;
-use std::marker::PhantomData;
-use std::sync::Arc;
+    // Get the Tags applied to a row
+    pub fn row_tags(&self, row: usize) -> Result<&TagSet, MatricalError> {
+        self.row_tags.get(row).ok_or(MatricalError::IndexOutOfBounds {
+            index: Index::new(row, 0),
+            shape: self.shape(),
+        })
+    }
 
-use crossbeam::atomic::AtomicCell;
-use crossbeam::queue::{ArrayQueue, SegQueue};
+    // Get the Tags applied to a column
+    pub fn col_tags(&self, col: usize) -> Result<&TagSet, MatricalError> {
+        self.col_tags.get(col).ok_or(MatricalError::IndexOutOfBounds {
+            index: Index::new(0, col),
+            shape: self.shape(),
+        })
+    }
 
-pub struct Matrix<V> {
-    matrix: ArrayQueue<Element<V>>,
-    _context: MatrixContext,
-}
+    // Copy the Tags of the given rows and columns, in the order given
+    pub(crate) fn select<R, C>(&self, rows: R, cols: C) -> MatrixContext
+    where
+        R: IntoIterator<Item = usize>,
+        C: IntoIterator<Item = usize>,
+    {
+        Self {
+            tags: self.tags.clone(),
+            row_tags: rows.into_iter().map(|row| self.row_tags[row].clone()).collect(),
+            col_tags: cols.into_iter().map(|col| self.col_tags[col].clone()).collect(),
+        }
+    }
 
-pub struct MatrixContext {
-    dimensions: Option<(usize, usize)>,
-    attributes: Vec<Tag>,
-    functors: HashMap<usize, Arc<Box<dyn Fn(dyn Any + Send + Sync) -> ()>>>,
+    fn shape(&self) -> Shape {
+        Shape::new(self.row_tags.len(), self.col_tags.len()).expect("tag lists match a valid shape")
+    }
 }
 
 impl<V> Matrix<V> {
-    pub fn new_by_size(size: usize) -> Self {
+    // Create a new Matrix from row-major values
+    pub fn from_shape_vec(shape: (usize, usize), values: Vec<V>) -> Result<Self, MatricalError> {
+        let expected = Shape::try_from(shape)?.len();
+        if values.len() != expected {
+            return Err(MatricalError::LengthMismatch {
+                expected,
+                actual: values.len(),
+            });
+        }
+        let data = Array2::from_shape_vec(shape, values).expect("length checked above");
+        Ok(Self::from_array(data))
+    }
+
+    // Create a new Matrix filled with zeros
+    pub fn zeros(shape: (usize, usize)) -> Result<Self, MatricalError>
+    where
+        V: Clone + Zero,
+    {
+        Shape::try_from(shape)?;
+        Ok(Self::from_array(Array2::zeros(shape)))
+    }
+
+    // Create a new Matrix by calling `f(row, col)` for every cell in row-major order
+    pub fn from_fn<F>(shape: (usize, usize), mut f: F) -> Result<Self, MatricalError>
+    where
+        F: FnMut(usize, usize) -> V,
+    {
+        Shape::try_from(shape)?;
+        Ok(Self::from_array(Array2::from_shape_fn(shape, |(row, col)| f(row, col))))
+    }
+
+    // Wrap an existing array; its shape is already valid
+    pub fn from_array(data: Array2<V>) -> Self {
+        let (rows, cols) = data.dim();
+        let shape = Shape::new(rows, cols).expect("array storage has a valid shape");
         Self {
-            matrix: ArrayQueue::new(size),
-            _context: MatrixContext {
-                dimensions: None,
-                attributes: Vec::new(),
-                functors: HashMap::new(),
-            },
+            data,
+            context: MatrixContext::new(shape),
         }
     }
+
+    // Wrap an existing array together with its Tags; the Tags must match its shape
+    pub(crate) fn from_parts(data: Array2<V>, context: MatrixContext) -> Self {
+        debug_assert_eq!(data.dim(), (context.row_tags.len(), context.col_tags.len()));
+        Self { data, context }
+    }
+
+    // Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.data.nrows()
+    }
+
+    // Get the number of columns
+    pub fn cols(&self) -> usize {
+        self.data.ncols()
+    }
+
+    // Get the validated shape
+    pub fn shape(&self) -> Shape {
+        let (rows, cols) = self.data.dim();
+        // The storage already holds rows * cols elements, so this cannot overflow
+        Shape::new(rows, cols).expect("matrix storage has a valid shape")
+    }
+
+    // Get the number of elements
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    // Check whether the Matrix has no elements
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // Get a reference to the value at (row, col)
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<&V, MatricalError> {
+        let index = self.shape().check_index(index.into())?;
+        Ok(&self.data[(index.row(), index.col())])
+    }
+
+    // Get a mutable reference to the value at (row, col)
+    pub fn get_mut<I: Into<Index>>(&mut self, index: I) -> Result<&mut V, MatricalError> {
+        let index = self.shape().check_index(index.into())?;
+        Ok(&mut self.data[(index.row(), index.col())])
+    }
+
+    // Replace the value at (row, col), returning the previous value
+    pub fn set<I: Into<Index>>(&mut self, index: I, value: V) -> Result<V, MatricalError> {
+        Ok(std::mem::replace(self.get_mut(index)?, value))
+    }
+
+    // Iterate over the values in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &V> {
+        self.data.iter()
+    }
+
+    // Iterate mutably over the values in row-major order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.data.iter_mut()
+    }
+
+    // Get the metadata carried by the Matrix
+    pub fn context(&self) -> &MatrixContext {
+        &self.context
+    }
+
+    // Get the Tags applied to the whole Matrix
+    pub fn tags(&self) -> &TagSet {
+        &self.context.tags
+    }
+
+    // Mutably get the Tags applied to the whole Matrix
+    pub fn tags_mut(&mut self) -> &mut TagSet {
+        &mut self.context.tags
+    }
+
+    // Get the Tags applied to a row
+    pub fn row_tags(&self, row: usize) -> Result<&TagSet, MatricalError> {
+        self.context.row_tags(row)
+    }
+
+    // Mutably get the Tags applied to a row
+    pub fn row_tags_mut(&mut self, row: usize) -> Result<&mut TagSet, MatricalError> {
+        let shape = self.shape();
+        self.context.row_tags.get_mut(row).ok_or(MatricalError::IndexOutOfBounds {
+            index: Index::new(row, 0),
+            shape,
+        })
+    }
+
+    // Get the Tags applied to a column
+    pub fn col_tags(&self, col: usize) -> Result<&TagSet, MatricalError> {
+        self.context.col_tags(col)
+    }
+
+    // Mutably get the Tags applied to a column
+    pub fn col_tags_mut(&mut self, col: usize) -> Result<&mut TagSet, MatricalError> {
+        let shape = self.shape();
+        self.context.col_tags.get_mut(col).ok_or(MatricalError::IndexOutOfBounds {
+            index: Index::new(0, col),
+            shape,
+        })
+    }
+
+    // Borrow the underlying storage
+    pub fn as_array(&self) -> &Array2<V> {
+        &self.data
+    }
+
+    // Mutably borrow the storage while sharing the metadata
+    pub(crate) fn parts_mut(&mut self) -> (&mut Array2<V>, &MatrixContext) {
+        (&mut self.data, &self.context)
+    }
+
+    // Consume the Matrix and return the underlying storage
+    pub fn into_array(self) -> Array2<V> {
+        self.data
+    }
 }
 
+impl<V> From<Array2<V>> for Matrix<V> {
+    fn from(data: Array2<V>) -> Self {
+        Self::from_array(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::tag::{Tag, TagKey};
+
+    #[test]
+    fn test_from_shape_vec() {
+        let matrix = Matrix::from_shape_vec((2, 3), vec![1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(matrix.rows(), 2);
+        assert_eq!(matrix.cols(), 3);
+        assert_eq!(*matrix.get((0, 2)).unwrap(), 3);
+        assert_eq!(*matrix.get((1, 0)).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_from_shape_vec_rejects_wrong_length() {
+        assert!(matches!(
+            Matrix::from_shape_vec((2, 3), vec![1, 2, 3]),
+            Err(MatricalError::LengthMismatch { expected: 6, actual: 3 })
+        ));
+    }
 
-// pub struct Matrix<V> { matrix: ArrayQueue<Element<V>>, _context: MatrixContext }
+    #[test]
+    fn test_zeros_and_set() {
+        let mut matrix: Matrix<f64> = Matrix::zeros((3, 2)).unwrap();
+        assert_eq!(matrix.set((2, 1), 4.0).unwrap(), 0.0);
+        *matrix.get_mut((0, 0)).unwrap() = 1.0;
+        assert_eq!(matrix.iter().copied().collect::<Vec<_>>(), vec![1.0, 0.0, 0.0, 0.0, 0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_from_fn_is_row_major() {
+        let matrix = Matrix::from_fn((2, 2), |row, col| row * 10 + col).unwrap();
+        assert_eq!(matrix.iter().copied().collect::<Vec<_>>(), vec![0, 1, 10, 11]);
+    }
 
-// pub struct MatrixContext {
-//     dimensions: Option<(usize, usize)>,
-//     attributes: Vec<Tag>,
-//     functors: HashMap<usize, Arc<Box<dyn Fn( dyn Any + Send + Sync )>>>,
-// }
+    #[test]
+    fn test_out_of_bounds() {
+        let mut matrix: Matrix<i32> = Matrix::zeros((2, 2)).unwrap();
+        assert!(matrix.get((2, 0)).is_err());
+        assert!(matrix.get((0, 2)).is_err());
+        assert!(matrix.set(Index::new(5, 5), 1).is_err());
+        assert!(matrix.get(Index::new(1, 1)).is_ok());
+    }
 
-// // The Matrix struct now holds a Box<dyn MatrixOperation> which allows for changing the operation at runtime
+    #[test]
+    fn test_overflowing_shape() {
+        assert!(Matrix::<u8>::zeros((usize::MAX, 2)).is_err());
+        assert!(Matrix::from_fn((usize::MAX / 2, 4), |_, _| 0u8).is_err());
+    }
 
-// impl<V> Matrix<V> {
-//     pub fn new_by_size(size: usize) -> Self {
-//          Self {
-//              matrix: ArrayQueue::new(size),
-//              _context: MatrixContext { 
-//                  dimensions: None,
-//                  attributes: HashMap::new(),
-//                  functors: HashMap::new(),
-//              },
-//          }
-//      }
-// }
- 
- 
+    #[test]
+    fn test_row_and_col_tags() {
+        let mut matrix: Matrix<f64> = Matrix::zeros((2, 3)).unwrap();
+        matrix.col_tags_mut(1).unwrap().insert(Tag::unit("ms").unwrap()).unwrap();
+        matrix.tags_mut().insert(Tag::feature_schema("typing-v3").unwrap()).unwrap();
 
+        assert_eq!(matrix.col_tags(1).unwrap().get(&TagKey::Unit).unwrap().value(), "ms");
+        assert!(matrix.col_tags(0).unwrap().is_empty());
+        assert!(matrix.row_tags(1).unwrap().is_empty());
+        assert!(matches!(matrix.col_tags(3), Err(MatricalError::IndexOutOfBounds { .. })));
+        assert!(matrix.row_tags_mut(2).is_err());
+        assert_eq!(matrix.tags().len(), 1);
+    }
 
+    #[test]
+    fn test_zero_sized() {
+        let matrix: Matrix<f64> = Matrix::zeros((0, 4)).unwrap();
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.cols(), 4);
+        assert!(matrix.get((0, 0)).is_err());
+    }
+}
@@ -2,13 +2,19 @@
 
 pub mod data;
 pub mod element;
+pub mod mask;
+pub mod masked;
 pub mod matrix;
+pub mod shape;
 pub mod vector;
 
 
 pub use data::*;
 pub use element::*;
+pub use mask::*;
+pub use masked::*;
 pub use matrix::*;
+pub use shape::*;
 pub use vector::*;
 
 
@@ -0,0 +1,484 @@
+use crate::error::MatricalError;
+
+use serde::{Deserialize, Serialize};
+
+use std::ops::Range;
+
+// The (rows, cols) extent of a Matrix or a selection within one
+//
+// A Shape can only be built when `rows * cols` fits in the addressable range
+// of the underlying storage, so code holding a Shape never has to re-check
+// the element count for overflow.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedShape")]
+pub struct Shape {
+    rows: usize,
+    cols: usize,
+}
+
+impl Shape {
+    // Create a new Shape, rejecting dimensions whose element count overflows
+    pub fn new(rows: usize, cols: usize) -> Result<Self, MatricalError> {
+        rows.checked_mul(cols)
+            .filter(|len| *len <= isize::MAX as usize)
+            .map(|_| Self { rows, cols })
+            .ok_or(MatricalError::InvalidShape { rows, cols })
+    }
+
+    // Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // Get the number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // Get the number of elements; cannot overflow by construction
+    pub fn len(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    // Check whether the Shape has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Check whether the index addresses an element inside this Shape
+    pub fn contains(&self, index: Index) -> bool {
+        index.row < self.rows && index.col < self.cols
+    }
+
+    // Return the index if it is inside this Shape
+    pub fn check_index(&self, index: Index) -> Result<Index, MatricalError> {
+        if self.contains(index) {
+            Ok(index)
+        } else {
+            Err(MatricalError::IndexOutOfBounds { index, shape: *self })
+        }
+    }
+
+    // Return the region if it lies entirely inside this Shape
+    pub fn check_region(&self, region: Region) -> Result<Region, MatricalError> {
+        if self.region().contains_region(&region) {
+            Ok(region)
+        } else {
+            Err(MatricalError::RegionOutOfBounds { region, shape: *self })
+        }
+    }
+
+    // The Region covering every element of this Shape
+    pub fn region(&self) -> Region {
+        Region {
+            start: Index::new(0, 0),
+            end: Index::new(self.rows, self.cols),
+        }
+    }
+}
+
+impl TryFrom<(usize, usize)> for Shape {
+    type Error = MatricalError;
+
+    fn try_from((rows, cols): (usize, usize)) -> Result<Self, Self::Error> {
+        Self::new(rows, cols)
+    }
+}
+
+// Deserialized Shapes go through the same overflow check as constructed ones
+#[derive(Deserialize)]
+struct UncheckedShape {
+    rows: usize,
+    cols: usize,
+}
+
+impl TryFrom<UncheckedShape> for Shape {
+    type Error = MatricalError;
+
+    fn try_from(shape: UncheckedShape) -> Result<Self, Self::Error> {
+        Shape::new(shape.rows, shape.cols)
+    }
+}
+
+impl From<Shape> for (usize, usize) {
+    fn from(shape: Shape) -> Self {
+        (shape.rows, shape.cols)
+    }
+}
+
+// A (row, col) position
+//
+// An Index carries no bounds of its own; it is checked against a Shape or a
+// Region at the point of access.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Index {
+    row: usize,
+    col: usize,
+}
+
+impl Index {
+    // Create a new Index
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    // Get the row
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    // Get the column
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    // Add an offset to both coordinates, failing on overflow
+    pub fn checked_add(&self, offset: Index) -> Option<Index> {
+        Some(Index::new(
+            self.row.checked_add(offset.row)?,
+            self.col.checked_add(offset.col)?,
+        ))
+    }
+}
+
+impl From<(usize, usize)> for Index {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+impl From<Index> for (usize, usize) {
+    fn from(index: Index) -> Self {
+        (index.row, index.col)
+    }
+}
+
+// The lanes a per-lane operation treats independently
+//
+// `Column` works on each column on its own, so values run down the rows;
+// `Row` works on each row on its own, so values run across the columns.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    Column,
+    Row,
+}
+
+impl Axis {
+    // The ndarray axis that values of one lane run along
+    pub(crate) fn lane_axis(self) -> ndarray::Axis {
+        match self {
+            Axis::Column => ndarray::Axis(0),
+            Axis::Row => ndarray::Axis(1),
+        }
+    }
+}
+
+// A rectangular selection of rows and columns
+//
+// Stored half-open: `start` is the first selected index and `end` is one past
+// the last row and column. Regions can be built from either half-open or
+// inclusive corners; both constructors reject inverted corners, and the
+// inclusive constructor rejects corners whose exclusive end would overflow.
+// A Region with zero rows or zero columns is empty and selects nothing; only
+// the half-open constructor can build one.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedRegion")]
+pub struct Region {
+    start: Index,
+    end: Index,
+}
+
+impl Region {
+    // Create a new half-open Region covering `start..end`
+    pub fn new(start: Index, end: Index) -> Result<Self, MatricalError> {
+        if start.row > end.row || start.col > end.col {
+            return Err(MatricalError::InvalidRegion { start, end });
+        }
+        Shape::new(end.row - start.row, end.col - start.col)?;
+        Ok(Self { start, end })
+    }
+
+    // Create a new Region covering `top_left..=bottom_right`
+    pub fn inclusive(top_left: Index, bottom_right: Index) -> Result<Self, MatricalError> {
+        let invalid = MatricalError::InvalidRegion {
+            start: top_left,
+            end: bottom_right,
+        };
+        if top_left.row > bottom_right.row || top_left.col > bottom_right.col {
+            return Err(invalid);
+        }
+        let end = bottom_right.checked_add(Index::new(1, 1)).ok_or(invalid)?;
+        Self::new(top_left, end)
+    }
+
+    // Create a new Region of the given shape with its top left corner at `origin`
+    pub fn at(origin: Index, shape: Shape) -> Result<Self, MatricalError> {
+        let end = origin
+            .checked_add(Index::new(shape.rows, shape.cols))
+            .ok_or(MatricalError::RegionOverflow { origin, shape })?;
+        Ok(Self { start: origin, end })
+    }
+
+    // Get the first selected index
+    pub fn start(&self) -> Index {
+        self.start
+    }
+
+    // Get the exclusive end of the selection
+    pub fn end(&self) -> Index {
+        self.end
+    }
+
+    // Get the last selected index, or None when the Region is empty
+    pub fn bottom_right(&self) -> Option<Index> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(Index::new(self.end.row - 1, self.end.col - 1))
+        }
+    }
+
+    // Get the selected rows
+    pub fn row_range(&self) -> Range<usize> {
+        self.start.row..self.end.row
+    }
+
+    // Get the selected columns
+    pub fn col_range(&self) -> Range<usize> {
+        self.start.col..self.end.col
+    }
+
+    // Get the number of selected rows
+    pub fn rows(&self) -> usize {
+        self.end.row - self.start.row
+    }
+
+    // Get the number of selected columns
+    pub fn cols(&self) -> usize {
+        self.end.col - self.start.col
+    }
+
+    // Get the shape of the selection
+    pub fn shape(&self) -> Shape {
+        Shape {
+            rows: self.rows(),
+            cols: self.cols(),
+        }
+    }
+
+    // Check whether the Region selects nothing
+    pub fn is_empty(&self) -> bool {
+        self.rows() == 0 || self.cols() == 0
+    }
+
+    // Check whether the index is selected by this Region
+    pub fn contains(&self, index: Index) -> bool {
+        self.row_range().contains(&index.row) && self.col_range().contains(&index.col)
+    }
+
+    // Check whether every index of `other` is selected by this Region
+    //
+    // An empty Region is contained by any Region whose bounds it does not exceed.
+    pub fn contains_region(&self, other: &Region) -> bool {
+        self.start.row <= other.start.row
+            && self.start.col <= other.start.col
+            && other.end.row <= self.end.row
+            && other.end.col <= self.end.col
+    }
+
+    // Get the overlap of two Regions, or None when they share no index
+    pub fn intersection(&self, other: &Region) -> Option<Region> {
+        let start = Index::new(self.start.row.max(other.start.row), self.start.col.max(other.start.col));
+        let end = Index::new(self.end.row.min(other.end.row), self.end.col.min(other.end.col));
+        if start.row < end.row && start.col < end.col {
+            Some(Region { start, end })
+        } else {
+            None
+        }
+    }
+
+    // Move the Region by `offset`, failing on overflow
+    pub fn offset(&self, offset: Index) -> Result<Region, MatricalError> {
+        let origin = self.start.checked_add(offset).ok_or(MatricalError::RegionOverflow {
+            origin: self.start,
+            shape: self.shape(),
+        })?;
+        Self::at(origin, self.shape())
+    }
+
+    // Iterate over the selected indices in row-major order
+    pub fn iter(&self) -> RegionIter {
+        RegionIter {
+            region: *self,
+            next: if self.is_empty() { None } else { Some(self.start) },
+        }
+    }
+}
+
+// Deserialized Regions go through the same corner checks as constructed ones
+#[derive(Deserialize)]
+struct UncheckedRegion {
+    start: Index,
+    end: Index,
+}
+
+impl TryFrom<UncheckedRegion> for Region {
+    type Error = MatricalError;
+
+    fn try_from(region: UncheckedRegion) -> Result<Self, Self::Error> {
+        Region::new(region.start, region.end)
+    }
+}
+
+impl IntoIterator for Region {
+    type Item = Index;
+    type IntoIter = RegionIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Row-major iterator over the indices of a Region
+#[derive(Debug, Clone)]
+pub struct RegionIter {
+    region: Region,
+    next: Option<Index>,
+}
+
+impl Iterator for RegionIter {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Index> {
+        let current = self.next?;
+        self.next = if current.col + 1 < self.region.end.col {
+            Some(Index::new(current.row, current.col + 1))
+        } else if current.row + 1 < self.region.end.row {
+            Some(Index::new(current.row + 1, self.region.start.col))
+        } else {
+            None
+        };
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.next {
+            None => 0,
+            Some(next) => {
+                (self.region.end.row - next.row - 1) * self.region.cols() + (self.region.end.col - next.col)
+            }
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for RegionIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_overflow() {
+        assert!(Shape::new(usize::MAX, 2).is_err());
+        assert!(Shape::new(usize::MAX, 0).is_ok());
+        assert_eq!(Shape::new(3, 4).unwrap().len(), 12);
+    }
+
+    #[test]
+    fn test_shape_contains() {
+        let shape = Shape::new(2, 3).unwrap();
+        assert!(shape.contains(Index::new(1, 2)));
+        assert!(!shape.contains(Index::new(2, 0)));
+        assert!(matches!(
+            shape.check_index(Index::new(0, 3)),
+            Err(MatricalError::IndexOutOfBounds { index, .. }) if index == Index::new(0, 3)
+        ));
+    }
+
+    #[test]
+    fn test_region_constructors_agree() {
+        let inclusive = Region::inclusive(Index::new(1, 1), Index::new(2, 3)).unwrap();
+        let half_open = Region::new(Index::new(1, 1), Index::new(3, 4)).unwrap();
+        assert_eq!(inclusive, half_open);
+        assert_eq!(inclusive.shape(), Shape::new(2, 3).unwrap());
+        assert_eq!(inclusive.bottom_right(), Some(Index::new(2, 3)));
+    }
+
+    #[test]
+    fn test_region_rejects_inverted_and_overflowing() {
+        assert!(matches!(
+            Region::new(Index::new(2, 0), Index::new(1, 5)),
+            Err(MatricalError::InvalidRegion { .. })
+        ));
+        assert!(Region::inclusive(Index::new(0, 3), Index::new(0, 2)).is_err());
+        assert!(Region::inclusive(Index::new(0, 0), Index::new(usize::MAX, 0)).is_err());
+        assert!(Region::new(Index::new(0, 0), Index::new(usize::MAX, usize::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_empty_region() {
+        let region = Region::new(Index::new(2, 2), Index::new(2, 3)).unwrap();
+        assert!(region.is_empty());
+        assert_eq!(region.bottom_right(), None);
+        assert_eq!(region.iter().count(), 0);
+        assert!(Shape::new(3, 3).unwrap().check_region(region).is_ok());
+    }
+
+    #[test]
+    fn test_check_region() {
+        let shape = Shape::new(3, 3).unwrap();
+        assert!(shape.check_region(Region::inclusive(Index::new(0, 0), Index::new(2, 2)).unwrap()).is_ok());
+
+        let outside = Region::inclusive(Index::new(1, 1), Index::new(3, 2)).unwrap();
+        match shape.check_region(outside) {
+            Err(MatricalError::RegionOutOfBounds { region, shape: checked }) => {
+                assert_eq!(region, outside);
+                assert_eq!(checked, shape);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = Region::new(Index::new(0, 0), Index::new(3, 3)).unwrap();
+        let b = Region::new(Index::new(2, 1), Index::new(5, 5)).unwrap();
+        assert_eq!(a.intersection(&b), Some(Region::new(Index::new(2, 1), Index::new(3, 3)).unwrap()));
+
+        let c = Region::new(Index::new(3, 0), Index::new(4, 3)).unwrap();
+        assert_eq!(a.intersection(&c), None);
+    }
+
+    #[test]
+    fn test_region_iter_row_major() {
+        let region = Region::inclusive(Index::new(1, 2), Index::new(2, 3)).unwrap();
+        let iter = region.iter();
+        assert_eq!(iter.len(), 4);
+        let indices: Vec<(usize, usize)> = iter.map(Into::into).collect();
+        assert_eq!(indices, vec![(1, 2), (1, 3), (2, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn test_offset() {
+        let region = Region::new(Index::new(0, 0), Index::new(2, 2)).unwrap();
+        assert_eq!(region.offset(Index::new(1, 3)).unwrap().start(), Index::new(1, 3));
+        assert!(region.offset(Index::new(usize::MAX, 0)).is_err());
+    }
+
+    #[test]
+    fn test_serde_checks_invariants() {
+        let region = Region::new(Index::new(1, 0), Index::new(3, 2)).unwrap();
+        let json = serde_json::to_string(&region).unwrap();
+        assert_eq!(serde_json::from_str::<Region>(&json).unwrap(), region);
+
+        let inverted = r#"{"start":{"row":3,"col":0},"end":{"row":1,"col":2}}"#;
+        assert!(serde_json::from_str::<Region>(inverted).is_err());
+        let huge = format!(r#"{{"rows":{},"cols":2}}"#, usize::MAX);
+        assert!(serde_json::from_str::<Shape>(&huge).is_err());
+    }
+}
@@ -0,0 +1,436 @@
+use crate::error::MatricalError;
+use crate::schematics::shape::{Index, Region, Shape};
+use crate::strategies::cog::Cog;
+use crate::strategies::gear::GearMut;
+use crate::strategies::lens::{Lens, LensMut};
+use crate::strategies::report::OperationId;
+use crate::strategies::statistics::{mean, median, variance};
+use crate::strategies::tag::Tag;
+
+use num_traits::Float;
+use serde::{Deserialize, Serialize};
+
+// The rows of a Lens that make up a baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BaselineWindow {
+    // The half-open range of Lens-relative rows start..end
+    Rows { start: usize, end: usize },
+    // The last `n` rows of the Lens
+    Trailing(usize),
+    // Every row carrying the Tag
+    Tagged(Tag),
+}
+
+impl BaselineWindow {
+    // Resolve the window to Lens-relative rows of `input`
+    fn rows<V>(&self, input: &Lens<'_, V>) -> Result<Vec<usize>, MatricalError> {
+        let shape = input.shape();
+        let rows = match self {
+            BaselineWindow::Rows { start, end } => {
+                let region = Region::new(Index::new(*start, 0), Index::new(*end, shape.cols()))?;
+                shape.check_region(region)?.row_range().collect()
+            }
+            BaselineWindow::Trailing(n) => {
+                if *n > shape.rows() {
+                    return Err(MatricalError::InsufficientData {
+                        operation: "baseline",
+                        required: *n,
+                        actual: shape.rows(),
+                    });
+                }
+                (shape.rows() - n..shape.rows()).collect()
+            }
+            BaselineWindow::Tagged(tag) => {
+                let mut rows = Vec::new();
+                for row in 0..shape.rows() {
+                    if input.row_tags(row)?.contains(tag) {
+                        rows.push(row);
+                    }
+                }
+                rows
+            }
+        };
+        Ok(rows)
+    }
+
+    fn id(&self) -> String {
+        match self {
+            BaselineWindow::Rows { start, end } => format!("rows={}..{}", start, end),
+            BaselineWindow::Trailing(n) => format!("trailing={}", n),
+            BaselineWindow::Tagged(tag) => format!("tag={}", tag),
+        }
+    }
+}
+
+// The center and scale statistics fitted to each baseline column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BaselineStatistic {
+    // Mean and standard deviation with `ddof` delta degrees of freedom
+    MeanStd { ddof: usize },
+    // Median and median absolute deviation
+    MedianMad,
+}
+
+impl BaselineStatistic {
+    fn fit(&self, values: &[f64]) -> (f64, f64) {
+        match *self {
+            BaselineStatistic::MeanStd { ddof } => (mean(values), variance(values, ddof).sqrt()),
+            BaselineStatistic::MedianMad => {
+                let center = median(values);
+                let deviations: Vec<f64> = values.iter().map(|value| (value - center).abs()).collect();
+                (center, median(&deviations))
+            }
+        }
+    }
+
+    fn required(&self) -> usize {
+        match *self {
+            BaselineStatistic::MeanStd { ddof } => ddof + 1,
+            BaselineStatistic::MedianMad => 1,
+        }
+    }
+
+    fn id(&self) -> String {
+        match self {
+            BaselineStatistic::MeanStd { ddof } => format!("mean_std,ddof={}", ddof),
+            BaselineStatistic::MedianMad => "median_mad".to_string(),
+        }
+    }
+}
+
+// The BaselineCog struct
+//
+// A personal baseline fitted once from selected rows of a Lens: one center
+// and one scale per column. The fitted statistics are plain data, so a
+// BaselineCog can be serialized, stored, and later supplied as the policy of
+// `Residual` or `ZScoreAgainstBaseline` on new data with the same columns.
+// Deserializing rejects a baseline whose centers and scales disagree in length.
+//
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedBaselineCog")]
+pub struct BaselineCog {
+    window: BaselineWindow,
+    statistic: BaselineStatistic,
+    center: Vec<f64>,
+    scale: Vec<f64>,
+}
+
+impl BaselineCog {
+    // Fit a baseline to the rows of `input` selected by `window`
+    pub fn fit<V: Float>(
+        input: &Lens<'_, V>,
+        window: BaselineWindow,
+        statistic: BaselineStatistic,
+    ) -> Result<Self, MatricalError> {
+        let rows = window.rows(input)?;
+        if rows.len() < statistic.required() {
+            return Err(MatricalError::InsufficientData {
+                operation: "baseline",
+                required: statistic.required(),
+                actual: rows.len(),
+            });
+        }
+
+        let view = input.view();
+        let mut center = Vec::with_capacity(input.cols());
+        let mut scale = Vec::with_capacity(input.cols());
+        for col in 0..input.cols() {
+            let values: Vec<f64> = rows
+                .iter()
+                .map(|&row| view[(row, col)].to_f64().unwrap_or(f64::NAN))
+                .collect();
+            let (c, s) = statistic.fit(&values);
+            center.push(c);
+            scale.push(s);
+        }
+
+        Ok(Self {
+            window,
+            statistic,
+            center,
+            scale,
+        })
+    }
+
+    // Get the window the baseline was fitted to
+    pub fn window(&self) -> &BaselineWindow {
+        &self.window
+    }
+
+    // Get the statistic the baseline was fitted with
+    pub fn statistic(&self) -> BaselineStatistic {
+        self.statistic
+    }
+
+    // Get the number of columns the baseline covers
+    pub fn cols(&self) -> usize {
+        self.center.len()
+    }
+
+    // Get the per-column centers
+    pub fn center(&self) -> &[f64] {
+        &self.center
+    }
+
+    // Get the per-column scales
+    pub fn scale(&self) -> &[f64] {
+        &self.scale
+    }
+
+    fn check<V>(&self, target: &LensMut<'_, V>, operation: &'static str) -> Result<(), MatricalError> {
+        // `fit` and deserialization both guarantee one scale per center
+        debug_assert_eq!(self.scale.len(), self.center.len());
+        if target.cols() != self.cols() {
+            return Err(MatricalError::ShapeMismatch {
+                operation,
+                expected: Shape::new(target.rows(), self.cols())?,
+                actual: target.shape(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct UncheckedBaselineCog {
+    window: BaselineWindow,
+    statistic: BaselineStatistic,
+    center: Vec<f64>,
+    scale: Vec<f64>,
+}
+
+impl TryFrom<UncheckedBaselineCog> for BaselineCog {
+    type Error = MatricalError;
+
+    fn try_from(baseline: UncheckedBaselineCog) -> Result<Self, Self::Error> {
+        if baseline.scale.len() != baseline.center.len() {
+            return Err(MatricalError::LengthMismatch {
+                expected: baseline.center.len(),
+                actual: baseline.scale.len(),
+            });
+        }
+        Ok(Self {
+            window: baseline.window,
+            statistic: baseline.statistic,
+            center: baseline.center,
+            scale: baseline.scale,
+        })
+    }
+}
+
+// A 64-bit FNV-1a digest of the fitted values, stable across runs and builds
+fn digest(center: &[f64], scale: &[f64]) -> u64 {
+    center
+        .iter()
+        .chain(scale)
+        .flat_map(|value| value.to_bits().to_le_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+// The id names the fitted values too, so reports tell different baselines apart
+impl Cog for BaselineCog {
+    fn id(&self) -> String {
+        format!(
+            "baseline({};{};cols={};{:016x})",
+            self.window.id(),
+            self.statistic.id(),
+            self.cols(),
+            digest(&self.center, &self.scale)
+        )
+    }
+}
+
+// Rewrite every value of `target` as `(value - center[col]) / scale[col]`
+fn against_baseline<V: Float>(target: &mut LensMut<'_, V>, center: &[f64], scale: &[f64]) {
+    let mut view = target.view_mut();
+    for (col, mut lane) in view.columns_mut().into_iter().enumerate() {
+        let c = V::from(center[col]).unwrap_or_else(V::nan);
+        let s = V::from(scale[col]).unwrap_or_else(V::nan);
+        lane.mapv_inplace(|value| (value - c) / s);
+    }
+}
+
+// The Residual struct, subtracting the baseline center from each column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Residual;
+
+impl<V: Float> GearMut<V> for Residual {
+    type Policy = BaselineCog;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &BaselineCog) -> Result<(), MatricalError> {
+        policy.check(target, "residual")?;
+        against_baseline(target, &policy.center, &vec![1.0; policy.cols()]);
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("residual", 1)
+    }
+}
+
+// The ZScoreAgainstBaseline struct, standardizing each column by the baseline
+// center and scale; a zero baseline scale is treated as one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ZScoreAgainstBaseline;
+
+impl<V: Float> GearMut<V> for ZScoreAgainstBaseline {
+    type Policy = BaselineCog;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &BaselineCog) -> Result<(), MatricalError> {
+        policy.check(target, "z_score_against_baseline")?;
+        let scale: Vec<f64> = policy
+            .scale
+            .iter()
+            .map(|&s| if s == 0.0 { 1.0 } else { s })
+            .collect();
+        against_baseline(target, &policy.center, &scale);
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("z_score_against_baseline", 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::matrix::Matrix;
+
+    fn history() -> Matrix<f64> {
+        Matrix::from_shape_vec(
+            (5, 2),
+            vec![
+                1.0, 10.0, //
+                3.0, 10.0, //
+                5.0, 10.0, //
+                7.0, 20.0, //
+                9.0, 30.0,
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fit_rows() {
+        let matrix = history();
+        let baseline = BaselineCog::fit(
+            &matrix.as_lens(),
+            BaselineWindow::Rows { start: 0, end: 3 },
+            BaselineStatistic::MeanStd { ddof: 1 },
+        )
+        .unwrap();
+        assert_eq!(baseline.center(), &[3.0, 10.0]);
+        assert_eq!(baseline.scale(), &[2.0, 0.0]);
+        assert!(baseline.id().starts_with("baseline(rows=0..3;mean_std,ddof=1;cols=2;"));
+
+        // Refits of the same rows keep the id; different data changes it
+        let refit = BaselineCog::fit(
+            &matrix.as_lens(),
+            BaselineWindow::Rows { start: 0, end: 3 },
+            BaselineStatistic::MeanStd { ddof: 1 },
+        )
+        .unwrap();
+        assert_eq!(refit.id(), baseline.id());
+        let shifted = Matrix::from_shape_vec((3, 2), vec![2.0, 10.0, 4.0, 10.0, 6.0, 10.0]).unwrap();
+        let other = BaselineCog::fit(
+            &shifted.as_lens(),
+            BaselineWindow::Rows { start: 0, end: 3 },
+            BaselineStatistic::MeanStd { ddof: 1 },
+        )
+        .unwrap();
+        assert_ne!(other.id(), baseline.id());
+    }
+
+    #[test]
+    fn test_fit_trailing_and_tagged() {
+        let matrix = history();
+        let trailing = BaselineCog::fit(&matrix.as_lens(), BaselineWindow::Trailing(2), BaselineStatistic::MedianMad).unwrap();
+        assert_eq!(trailing.center(), &[8.0, 25.0]);
+        assert_eq!(trailing.scale(), &[1.0, 5.0]);
+
+        let mut matrix = matrix;
+        let calm = Tag::custom("condition", "calm").unwrap();
+        for row in [0, 2, 4] {
+            matrix.row_tags_mut(row).unwrap().insert(calm.clone()).unwrap();
+        }
+        let window = BaselineWindow::Tagged(calm);
+        let tagged = BaselineCog::fit(&matrix.as_lens(), window.clone(), BaselineStatistic::MedianMad).unwrap();
+        assert_eq!(tagged.center(), &[5.0, 10.0]);
+
+        // The window follows the Lens: only row 2 of rows 1..3 is calm
+        let region = Region::new(Index::new(1, 0), Index::new(3, 2)).unwrap();
+        let lens = matrix.lens(region).unwrap();
+        assert!(matches!(
+            BaselineCog::fit(&lens, window, BaselineStatistic::MeanStd { ddof: 1 }),
+            Err(MatricalError::InsufficientData { required: 2, actual: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_fit_rejects_bad_windows() {
+        let matrix = history();
+        let lens = matrix.as_lens();
+        let statistic = BaselineStatistic::MeanStd { ddof: 1 };
+        assert!(matches!(
+            BaselineCog::fit(&lens, BaselineWindow::Rows { start: 2, end: 9 }, statistic),
+            Err(MatricalError::RegionOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            BaselineCog::fit(&lens, BaselineWindow::Trailing(1), statistic),
+            Err(MatricalError::InsufficientData { required: 2, actual: 1, .. })
+        ));
+        assert!(BaselineCog::fit(&lens, BaselineWindow::Trailing(6), statistic).is_err());
+    }
+
+    #[test]
+    fn test_residual_and_z_score() {
+        let matrix = history();
+        let baseline = BaselineCog::fit(
+            &matrix.as_lens(),
+            BaselineWindow::Rows { start: 0, end: 3 },
+            BaselineStatistic::MeanStd { ddof: 1 },
+        )
+        .unwrap();
+
+        let mut recent = Matrix::from_shape_vec((1, 2), vec![7.0, 20.0]).unwrap();
+        Residual.apply_mut(&mut recent.as_lens_mut(), &baseline).unwrap();
+        assert_eq!(recent.iter().copied().collect::<Vec<_>>(), vec![4.0, 10.0]);
+
+        let mut recent = Matrix::from_shape_vec((1, 2), vec![7.0, 20.0]).unwrap();
+        ZScoreAgainstBaseline.apply_mut(&mut recent.as_lens_mut(), &baseline).unwrap();
+        assert_eq!(recent.iter().copied().collect::<Vec<_>>(), vec![2.0, 10.0]);
+
+        let mut wide: Matrix<f64> = Matrix::zeros((1, 3)).unwrap();
+        assert!(matches!(
+            Residual.apply_mut(&mut wide.as_lens_mut(), &baseline),
+            Err(MatricalError::ShapeMismatch { operation: "residual", .. })
+        ));
+    }
+
+    #[test]
+    fn test_baseline_round_trips_through_serde() {
+        let matrix = history();
+        let baseline = BaselineCog::fit(&matrix.as_lens(), BaselineWindow::Trailing(3), BaselineStatistic::MedianMad).unwrap();
+
+        let json = serde_json::to_string(&baseline).unwrap();
+        let restored: BaselineCog = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, baseline);
+
+        let mut tomorrow = Matrix::from_shape_vec((1, 2), vec![9.0, 20.0]).unwrap();
+        ZScoreAgainstBaseline.apply_mut(&mut tomorrow.as_lens_mut(), &restored).unwrap();
+        assert_eq!(tomorrow.iter().copied().collect::<Vec<_>>(), vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_mismatched_lengths() {
+        let json = r#"{"window":{"Trailing":3},"statistic":"MedianMad","center":[1.0,2.0],"scale":[1.0]}"#;
+        assert!(serde_json::from_str::<BaselineCog>(json).is_err());
+
+        let json = r#"{"window":{"Trailing":3},"statistic":"MedianMad","center":[1.0,2.0],"scale":[1.0,0.5]}"#;
+        let baseline: BaselineCog = serde_json::from_str(json).unwrap();
+        assert_eq!(baseline.cols(), 2);
+    }
+}
@@ -1,431 +1,248 @@
-
 use crate::error::MatricalError;
-use ndarray::Array2;
-use std::marker::PhantomData;
-use std::sync::Arc;
-use std::any::Any;
+use crate::strategies::gear::{Gear, GearMut};
+use crate::strategies::lens::{Lens, LensMut};
+use crate::strategies::report::StageReport;
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+
+// The Cog trait
+//
+// A Cog is typed policy or context consulted by a Gear: "ddof = 1", "the
+// baseline is the first N rows", a validation rule. Cogs are ordinary structs
+// implementing this trait, and every Gear names the Cog it needs as its
+// `Policy`, so calling a Gear without its policy does not compile. `id`
+// identifies the policy and its parameters for execution reports.
+//
+pub trait Cog: Any + Send + Sync {
+    fn id(&self) -> String;
+}
 
+// The NoPolicy struct, the Policy of Gears that need no context
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NoPolicy;
 
+impl Cog for NoPolicy {
+    fn id(&self) -> String {
+        "none".to_string()
+    }
+}
 
+// Pairs of Cogs, for Gears that need two policies at once
+impl<A: Cog, B: Cog> Cog for (A, B) {
+    fn id(&self) -> String {
+        format!("{}+{}", self.0.id(), self.1.id())
+    }
+}
 
-// The Cog struct
-pub struct Cog {
-    lens: Option<Arc<dyn Fn()>>,
-    operation: Option<Box<dyn CogOperation>>,
-    strategy: Option<Box<dyn CogStrategy>>,
-    context: Option<Box<CogContext>>,
+// The Policies struct
+//
+// A runtime set of Cogs keyed by type, for Gears assembled dynamically where
+// the policy cannot be checked at compile time. Looking up a Cog that was
+// never supplied is a `MissingPolicy` error. `NoPolicy` is always present.
+// Policies is itself a Cog, the Policy of dynamically assembled pipelines.
+//
+pub struct Policies {
+    cogs: HashMap<TypeId, Box<dyn Cog>>,
 }
 
-impl Cog {
-    pub fn new(
-        lens: Option<Arc<dyn Fn()>>,
-        operation: Option<Box<dyn CogOperation>>,
-        strategy: Option<Box<dyn CogStrategy>>,
-        context: Option<Box<CogContext>>,
-    ) -> Self {
-        Self {
-            lens,
-            operation,
-            strategy,
-            context,
-        }
+impl Policies {
+    // Create a new Policies holding only NoPolicy
+    pub fn new() -> Self {
+        let mut policies = Self { cogs: HashMap::new() };
+        policies.insert(NoPolicy);
+        policies
+    }
+
+    // Add a Cog, returning the Cog of the same type it replaces
+    pub fn insert<C: Cog>(&mut self, cog: C) -> Option<C> {
+        self.cogs
+            .insert(TypeId::of::<C>(), Box::new(cog))
+            .and_then(|previous| (previous as Box<dyn Any>).downcast::<C>().ok())
+            .map(|previous| *previous)
+    }
+
+    // Add a Cog, builder style
+    pub fn with<C: Cog>(mut self, cog: C) -> Self {
+        self.insert(cog);
+        self
+    }
+
+    // Check whether a Cog of type C is present
+    pub fn contains<C: Cog>(&self) -> bool {
+        self.cogs.contains_key(&TypeId::of::<C>())
+    }
+
+    // Get the Cog of type C
+    pub fn get<C: Cog>(&self) -> Result<&C, MatricalError> {
+        self.cogs
+            .get(&TypeId::of::<C>())
+            .and_then(|cog| (cog.as_ref() as &dyn Any).downcast_ref::<C>())
+            .ok_or(MatricalError::MissingPolicy { policy: type_name::<C>() })
     }
 }
 
-// Cog Operation
-pub trait CogOperation: Send + Sync {
-    fn apply(&self, context: &CogContext) -> Result<(), MatricalError>;
+// The ids of every Cog present, sorted so the id does not depend on insertion order
+impl Cog for Policies {
+    fn id(&self) -> String {
+        let mut ids: Vec<String> = self.cogs.values().map(|cog| cog.id()).collect();
+        ids.sort();
+        ids.join("+")
+    }
 }
 
-pub struct CogOperationImpl {
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
+impl Default for Policies {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl CogOperationImpl {
-    pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-        }
+impl std::fmt::Debug for Policies {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Policies").field("len", &self.cogs.len()).finish()
     }
 }
 
-impl CogOperation for CogOperationImpl {
-    fn apply(&self, context: &CogContext) -> Result<(), MatricalError> {
-        // Check if the coordinates are within the matrix dimensions
-        if self.top_left.0 >= context.data.dim().0
-            || self.top_left.1 >= context.data.dim().1
-            || self.bottom_right.0 >= context.data.dim().0
-            || self.bottom_right.1 >= context.data.dim().1
-        {
-            return Err(MatricalError::ShouldNotOccur); // TODO: Change this to IndexOutOfBounds
-        }
+// The DynGear trait, an object-safe Gear that finds its policy in a Policies set
+pub trait DynGear<V> {
+    type Output;
+
+    fn apply_dyn(&self, input: &Lens<'_, V>, policies: &Policies) -> Result<Self::Output, MatricalError>;
+
+    // Get the name of the Cog type the Gear requires
+    fn policy_name(&self) -> &'static str;
+}
 
-        // Apply the cog operation logic here
-        // ...
+impl<V, G: Gear<V>> DynGear<V> for G {
+    type Output = G::Output;
 
-        Ok(())
+    fn apply_dyn(&self, input: &Lens<'_, V>, policies: &Policies) -> Result<G::Output, MatricalError> {
+        self.apply(input, policies.get::<G::Policy>()?)
+    }
+
+    fn policy_name(&self) -> &'static str {
+        type_name::<G::Policy>()
     }
 }
 
-// Cog Strategy
-pub trait CogStrategy: Send + Sync {
-    fn execute(
+// The DynGearMut trait, an object-safe GearMut that finds its policy in a Policies set
+pub trait DynGearMut<V> {
+    fn apply_dyn_mut(&self, target: &mut LensMut<'_, V>, policies: &Policies) -> Result<(), MatricalError>;
+
+    // Apply the GearMut, recording the stages it runs
+    fn apply_dyn_mut_staged(
         &self,
-        cog: &Cog,
-        index: Option<(usize, usize)>,
-        other: Option<bool>,
+        target: &mut LensMut<'_, V>,
+        policies: &Policies,
+        stages: &mut Vec<StageReport>,
     ) -> Result<(), MatricalError>;
-}
 
-pub struct CogStrategyImpl {
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
+    // Get the name of the Cog type the GearMut requires
+    fn policy_name(&self) -> &'static str;
 }
 
-impl CogStrategyImpl {
-    pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-        }
+impl<V, G: GearMut<V>> DynGearMut<V> for G {
+    fn apply_dyn_mut(&self, target: &mut LensMut<'_, V>, policies: &Policies) -> Result<(), MatricalError> {
+        self.apply_mut(target, policies.get::<G::Policy>()?)
     }
-}
 
-impl CogStrategy for CogStrategyImpl {
-    fn execute(
+    fn apply_dyn_mut_staged(
         &self,
-        cog: &Cog,
-        index: Option<(usize, usize)>,
-        other: Option<bool>,
+        target: &mut LensMut<'_, V>,
+        policies: &Policies,
+        stages: &mut Vec<StageReport>,
     ) -> Result<(), MatricalError> {
-        // Check if the coordinates are within the matrix dimensions
-        if self.top_left.0 >= cog.context.as_ref().unwrap().data.dim().0
-            || self.top_left.1 >= cog.context.as_ref().unwrap().data.dim().1
-            || self.bottom_right.0 >= cog.context.as_ref().unwrap().data.dim().0
-            || self.bottom_right.1 >= cog.context.as_ref().unwrap().data.dim().1
-        {
-            return Err(MatricalError::ShouldNotOccur);  // TODO: Change this to IndexOutOfBounds
+        match policies.get::<G::Policy>() {
+            Ok(policy) => self.apply_mut_staged(target, policy, stages),
+            Err(error) => {
+                let operation = GearMut::operation(self);
+                stages.push(StageReport::failed(operation, type_name::<G::Policy>(), target.shape(), &error));
+                Err(error)
+            }
         }
-
-        // Execute the cog strategy logic here
-        // ...
-
-        Ok(())
     }
-}
 
-// Cog Context
-pub struct CogContext {
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
-    data: Array2<f64>,
+    fn policy_name(&self) -> &'static str {
+        type_name::<G::Policy>()
+    }
 }
 
-impl CogContext {
-    pub fn new(top_left: (usize, usize), bottom_right: (usize, usize), data: Array2<f64>) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-            data,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::matrix::Matrix;
+    use crate::schematics::shape::Axis;
+    use crate::strategies::gear::AddConstant;
+    use crate::strategies::report::OperationId;
+    use crate::strategies::statistics::{Ddof, ZScore};
+
+    // A downstream-style policy
+    #[derive(Debug, PartialEq)]
+    struct Offset(i32);
+
+    impl Cog for Offset {
+        fn id(&self) -> String {
+            format!("offset={}", self.0)
         }
     }
-}
 
-// Cog Builder
-pub struct CogBuilder {
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
-    data: Option<Array2<f64>>,
-}
+    struct Shift;
 
-impl CogBuilder {
-    pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-            data: None,
+    impl GearMut<i32> for Shift {
+        type Policy = Offset;
+
+        fn apply_mut(&self, target: &mut LensMut<'_, i32>, policy: &Offset) -> Result<(), MatricalError> {
+            target.map_inplace(|value| *value += policy.0);
+            Ok(())
+        }
+
+        fn operation(&self) -> OperationId {
+            OperationId::new("shift", 1)
         }
     }
 
-    pub fn data(mut self, data: Array2<f64>) -> Self {
-        self.data = Some(data);
-        self
+    #[test]
+    fn test_static_policy() {
+        let mut matrix: Matrix<i32> = Matrix::zeros((1, 2)).unwrap();
+        Shift.apply_mut(&mut matrix.as_lens_mut(), &Offset(3)).unwrap();
+        assert_eq!(matrix.iter().copied().collect::<Vec<_>>(), vec![3, 3]);
     }
 
-    pub fn build(self) -> Cog {
-        let context = self
-            .data
-            .map(|data| Box::new(CogContext::new(self.top_left, self.bottom_right, data)) as Box<CogContext>);
-
-        Cog::new(
-            None,
-            Some(Box::new(CogOperationImpl::new(self.top_left, self.bottom_right))),
-            Some(Box::new(CogStrategyImpl::new(self.top_left, self.bottom_right))),
-            context,
-        )
+    #[test]
+    fn test_policies_lookup() {
+        let mut policies = Policies::new().with(Offset(1));
+        assert!(policies.contains::<NoPolicy>());
+        assert_eq!(policies.insert(Offset(2)), Some(Offset(1)));
+        assert_eq!(policies.get::<Offset>().unwrap().id(), "offset=2");
+        assert!(matches!(policies.get::<Ddof>(), Err(MatricalError::MissingPolicy { .. })));
     }
-}
 
-fn main() {
-    let builder = CogBuilder::new((0, 0), (1, 1)).data(Array2::ones((2, 2)));
-    let cog = builder.build();
-    let context = cog.context.unwrap();
+    #[test]
+    fn test_dynamic_pipeline_reports_missing_policy() {
+        let mut matrix = Matrix::from_shape_vec((2, 1), vec![1.0, 3.0]).unwrap();
+        let gears: Vec<Box<dyn DynGearMut<f64>>> = vec![Box::new(AddConstant::new(1.0)), Box::new(ZScore::new(Axis::Column))];
 
-    let operation = cog.operation.unwrap();
-    operation.apply(&context).unwrap();
+        let policies = Policies::new();
+        let mut lens = matrix.as_lens_mut();
+        assert!(gears[0].apply_dyn_mut(&mut lens, &policies).is_ok());
+        let err = gears[1].apply_dyn_mut(&mut lens, &policies).unwrap_err();
+        assert!(matches!(err, MatricalError::MissingPolicy { policy } if policy == gears[1].policy_name()));
 
-    // let strategy = cog.strategy.unwrap().clone();
-    // strategy.execute(cog, None, None).unwrap();
-}
+        let policies = policies.with(Ddof(0));
+        gears[1].apply_dyn_mut(&mut lens, &policies).unwrap();
+        assert_eq!(matrix.iter().copied().collect::<Vec<_>>(), vec![-1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_pair_policy_id() {
+        assert_eq!((Offset(1), NoPolicy).id(), "offset=1+none");
+    }
 
-// The Cog struct
-// 
-/*
- * An Cog, in Matrical, is a piece of metadata that can be associated with a matrix or a submatrix.
- * It can store any kind of information, such as tags, labels, or significant data about the matrix.
- * Cogs can be used to provide additional context for operations performed on the matrix.
- * When a Gear operates on a matrix, it can use the information from any associated Cogs to
- * modify its operation. This allows for operations to be tailored based on the specific Cogs
- * of matrix elements. Cogs can be applied to the entire matrix or to specific submatrices.
- */
-// pub struct Cog { _attri: PhantomData<Arc<dyn Fn()>> }
-
-
-// // The Cog (Attribute) struct
-// #[derive(Debug, Clone)]
-// pub struct Cog {
-
-//     // For when a lens is required
-//     lens: Option<Arc<dyn Fn()>>,
-
-//     // The CogOperation to be applied to the Cog
-//     operation: Option<Box<dyn CogOperation>>,
-
-//     // The CogStrategy to be applied to the Cog
-//     strategy: Option<Box<dyn Any + Send + Sync + CogStrategy>>,
-
-//     // The CogContext to be applied to the Cog
-//     context: Option<Box<CogContext>>,
-   
-// }
-
-// impl Cog {
-//     // Create a new Cog with the given coordinates and data
-//     pub fn new(
-//         lens: Option<Arc<dyn Fn()>>,
-//         operation: Option<Box<dyn CogOperation>>,
-//         strategy: Option<Box<dyn CogStrategy>>,
-//         context: Option<Box<CogContext>>,
-//     ) -> Self {
-//         Self {
-//             lens,
-//             operation,
-//             strategy,
-//             context,
-//         }
-//     }
-// }
-        
-// // Cog Operation
-// // 
-// pub trait CogOperation {
-//     // Apply the CogOperation to the given Cog
-//     fn apply(&self, context: &CogContext) -> Result<(), MatricalError>;
-// }
-
-// // Cog Strategy
-// //
-// // The CogStrategy trait is used to provide context for CogOperations.
-// // Passing along the CogStrategy to the CogOperation allows the CogOperation
-// // to access the CogStrategy's context and perform operations on the Cog.
-// //  
-// pub trait CogStrategy {
-//     // Execute the CogStrategy on the given Cog
-//     fn execute(
-//         &self,
-//         cog: &Cog,
-//         // the index of the element in the matrix
-//         index: Option<(usize, usize)>,
-//         other: Option<bool>, // Placeholder TODO: What is this?
-//     ) -> Result<(), MatricalError>;
-// }
-// // The CogContext struct
-// //
-// // The CogContext struct is used to provide Metadata for CogOperations.
-// // It contains the dimensions coordinates of the sub-matrix
-// // where the Cog is applied.
-// //
-// #[derive(Debug, Clone)]
-// pub struct CogContext {
-//     // The top left and bottom right coordinates of the sub-matrix
-//     // where the Cog is applied
-//     top_left: (usize, usize),
-//     bottom_right: (usize, usize),
-//     // The data contained in the Cog
-//     data: Option<Array2<f64>>,
-//     // The lens of the Cog if it exists
-//     lens: Option<Arc<dyn Fn()>>,
-    
-// }
-
-// impl CogContext {
-//     // Create a new CogContext with the given coordinates
-//     pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-//         Self {
-//             top_left,
-//             bottom_right,
-//             lens: None,
-//             data: None,
-//         }
-//     }
-// }
-
-// // The CogOperation Implementation struct 
-// // 
-// #[derive(Debug, Clone)]
-// pub struct CogOperationImpl {
-//     // The top left and bottom right coordinates of the sub-matrix
-//     top_left: (usize, usize),
-//     bottom_right: (usize, usize),
-// }
-// impl CogOperationImpl {
-//     // Create a new CogOperation with the given coordinates
-//     pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-//         Self {
-//             top_left,
-//             bottom_right,
-//         }
-//     }
-// }
-
-// impl CogOperation for CogOperationImpl {
-//     fn apply(&self, context: &CogContext) -> Result<(), MatricalError> {
-//         // Check if the coordinates are within the matrix dimensions
-//         // TODO: Check if the coordinates are within the matrix dimensions
-//         //
-//         if self.top_left.0 >= context.top_left.0
-//             || self.top_left.1 >= context.top_left.1
-//             || self.bottom_right.0 >= context.bottom_right.0
-//             || self.bottom_right.1 >= context.bottom_right.1
-//         {
-//             return Err(MatricalError::IndexOutOfBounds);
-//         }
-
-//         Ok(())
-//     }
-// }
-
-// impl CogStrategy for CogOperationImpl {
-//     fn execute(
-//         &self,
-//         cog: &Cog,
-//         // the index of the element in the matrix
-//         _index: Option<(usize, usize)>,
-//         _other: Option<bool>, // Placeholder TODO: What is this?
-//     ) -> Result<(), MatricalError> {
-//         // Check if the coordinates are within the matrix dimensions
-//         if self.top_left.0 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().0
-//             || self.top_left.1 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().1
-//             || self.bottom_right.0 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().0
-//             || self.bottom_right.1 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().1
-//         {
-//             return Err(MatricalError::IndexOutOfBounds);
-//         }
-
-//         Ok(())
-//     }
-// }
-
-
-// // The CogStrategy struct
-// #[derive(Debug, Clone)]
-// pub struct CogStrategyImpl {
-//     // The top left and bottom right coordinates of the sub-matrix
-//     top_left: (usize, usize),
-//     bottom_right: (usize, usize),
-// }
-
-// impl CogStrategyImpl {
-//     // Create a new CogStrategy with the given coordinates
-//     pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-//         Self {
-//             top_left,
-//             bottom_right,
-//         }
-//     }
-// }
-
-// impl CogStrategy for CogStrategyImpl {
-//     fn execute(
-//         &self,
-//         cog: &Cog,
-//         _index: Option<(usize, usize)>,
-//         _other: Option<bool>, // Placeholder TODO: What is this?
-//     ) -> Result<(), MatricalError> {
-//         // Check if the coordinates are within the matrix dimensions
-//         if self.top_left.0 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().0
-//             || self.top_left.1 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().1
-//             || self.bottom_right.0 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().0
-//             || self.bottom_right.1 >= cog.context.as_ref().unwrap().data.as_ref().unwrap().dim().1
-//         {
-//             return Err(MatricalError::IndexOutOfBounds);
-//         }
-
-//         Ok(())
-//     }
-// }
-
-
-
-// // The CogBuilder struct
-// #[derive(Debug, Clone)]
-// pub struct CogBuilder {
-//     // The top left and bottom right coordinates of the sub-matrix
-//     // where the Cog is applied 
-//     // TODO: Should this be a reference to the Cog* struct?
-//     top_left: (usize, usize),
-//     bottom_right: (usize, usize),
-//     // The data contained in the Cog 
-//     // TODO: Should this be a reference to the Cog* struct?
-//     data: Array2<f64>,
-// }
-
-// impl CogBuilder {
-//     // Create a new CogBuilder with the given coordinates
-//     pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-//         Self {
-//             top_left,
-//             bottom_right,
-//             data: Array2::zeros((0, 0)),
-//         }
-//     }
-
-//     // Set the data of the CogBuilder
-//     // TODO: Should this be a reference to the Cog* struct?
-//     pub fn data(mut self, data: Array2<f64>) -> Self {
-//         self.data = data;
-//         self
-//     }
-
-//     // Build the Cog from the CogBuilder 
-//     // TODO: Should this be a reference to the Cog* struct?
-//     pub fn build(self) -> Cog {
-//         Cog::new(
-//             None,
-//             Some(Box::new(CogOperationImpl::new(
-//                 self.top_left,
-//                 self.bottom_right,
-//             ))),
-//             Some(Box::new(CogStrategyImpl::new(
-//                 self.top_left,
-//                 self.bottom_right,
-//             ))),
-//             Some(Box::new(CogContext::new(
-//                 self.top_left,
-//                 self.bottom_right,
-//             ))),
-//         )
-//     }
-// }
+    #[test]
+    fn test_policies_id_is_sorted() {
+        let first = Policies::new().with(Ddof(1)).with(Offset(2));
+        let second = Policies::new().with(Offset(2)).with(Ddof(1));
+        assert_eq!(first.id(), "ddof=1+none+offset=2");
+        assert_eq!(first.id(), second.id());
+    }
+}
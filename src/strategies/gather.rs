@@ -0,0 +1,442 @@
+use crate::error::MatricalError;
+use crate::schematics::mask::Mask;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Index, Shape};
+use crate::strategies::lens::{Lens, LensMut};
+use crate::strategies::selector::{Selection, SelectionMut, Selector};
+
+use ndarray::{Array2, IndexLonger};
+
+// A borrowed gather view over an arbitrary list of rows and columns of a Lens
+//
+// Row and column lists are kept in the order they were given and may repeat,
+// so a Gather can reorder or duplicate rows without copying any values.
+// Indices are validated against the source Lens when the Gather is created;
+// afterwards every index into the Gather is relative to its own shape.
+//
+#[derive(Debug, Clone)]
+pub struct Gather<'a, V> {
+    lens: Lens<'a, V>,
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    shape: Shape,
+}
+
+impl<'a, V> Gather<'a, V> {
+    // Get the shape of the gathered view
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    // Get the number of gathered cells
+    pub fn len(&self) -> usize {
+        self.shape.len()
+    }
+
+    // Check whether the Gather has no cells
+    pub fn is_empty(&self) -> bool {
+        self.shape.is_empty()
+    }
+
+    // Get the source Lens rows, in gathered order
+    pub fn row_indices(&self) -> &[usize] {
+        &self.rows
+    }
+
+    // Get the source Lens columns, in gathered order
+    pub fn col_indices(&self) -> &[usize] {
+        &self.cols
+    }
+
+    // Map a Gather-relative index to the source Lens index it reads
+    pub fn source_index<I: Into<Index>>(&self, index: I) -> Result<Index, MatricalError> {
+        let index = self.shape.check_index(index.into())?;
+        Ok(Index::new(self.rows[index.row()], self.cols[index.col()]))
+    }
+
+    // Get a reference to a gathered value
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<&'a V, MatricalError> {
+        let index = self.source_index(index)?;
+        self.lens.get(index)
+    }
+
+    // Gather rows of this Gather, relative to it
+    pub fn select_rows(&self, rows: &[usize]) -> Result<Gather<'a, V>, MatricalError> {
+        check_rows(rows, self.shape, false)?;
+        let rows = rows.iter().map(|&row| self.rows[row]).collect();
+        Gather::new(self.lens.reborrow(), rows, self.cols.clone())
+    }
+
+    // Gather columns of this Gather, relative to it
+    pub fn select_cols(&self, cols: &[usize]) -> Result<Gather<'a, V>, MatricalError> {
+        check_cols(cols, self.shape, false)?;
+        let cols = cols.iter().map(|&col| self.cols[col]).collect();
+        Gather::new(self.lens.reborrow(), self.rows.clone(), cols)
+    }
+
+    // Iterate over the gathered values in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &'a V> + '_ {
+        self.indexed_iter().map(|(_, value)| value)
+    }
+
+    // Iterate over the gathered values with their Gather-relative index
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Index, &'a V)> + '_ {
+        let view = self.lens.view();
+        self.shape.region().iter().map(move |index| {
+            let (row, col) = (self.rows[index.row()], self.cols[index.col()]);
+            (index, IndexLonger::index(&view, (row, col)))
+        })
+    }
+
+    // Copy the gathered values into a new Matrix
+    pub fn to_matrix(&self) -> Matrix<V>
+    where
+        V: Clone,
+    {
+        let (rows, cols) = self.shape.into();
+        let view = self.lens.view();
+        let data = Array2::from_shape_fn((rows, cols), |(row, col)| view[(self.rows[row], self.cols[col])].clone());
+        let origin = self.lens.region().start();
+        let context = self.lens.context().select(
+            self.rows.iter().map(|row| origin.row() + row),
+            self.cols.iter().map(|col| origin.col() + col),
+        );
+        Matrix::from_parts(data, context)
+    }
+
+    fn new(lens: Lens<'a, V>, rows: Vec<usize>, cols: Vec<usize>) -> Result<Self, MatricalError> {
+        let shape = Shape::new(rows.len(), cols.len())?;
+        Ok(Self { lens, rows, cols, shape })
+    }
+}
+
+// A mutable gather view that scatters writes back into a LensMut
+//
+// Unlike Gather, the row and column lists of a GatherMut may not repeat: every
+// gathered cell is a distinct cell of the source, so each write lands exactly
+// once.
+//
+#[derive(Debug)]
+pub struct GatherMut<'a, V> {
+    lens: LensMut<'a, V>,
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    shape: Shape,
+}
+
+impl<'a, V> GatherMut<'a, V> {
+    // Get the shape of the gathered view
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    // Get the number of gathered cells
+    pub fn len(&self) -> usize {
+        self.shape.len()
+    }
+
+    // Check whether the GatherMut has no cells
+    pub fn is_empty(&self) -> bool {
+        self.shape.is_empty()
+    }
+
+    // Get the source LensMut rows, in gathered order
+    pub fn row_indices(&self) -> &[usize] {
+        &self.rows
+    }
+
+    // Get the source LensMut columns, in gathered order
+    pub fn col_indices(&self) -> &[usize] {
+        &self.cols
+    }
+
+    // Borrow the gathered view immutably
+    pub fn as_gather(&self) -> Gather<'_, V> {
+        Gather {
+            lens: self.lens.as_lens(),
+            rows: self.rows.clone(),
+            cols: self.cols.clone(),
+            shape: self.shape,
+        }
+    }
+
+    // Get a reference to a gathered value
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<&V, MatricalError> {
+        let index = self.source_index(index.into())?;
+        self.lens.get(index)
+    }
+
+    // Get a mutable reference to a gathered value
+    pub fn get_mut<I: Into<Index>>(&mut self, index: I) -> Result<&mut V, MatricalError> {
+        let index = self.source_index(index.into())?;
+        self.lens.get_mut(index)
+    }
+
+    // Replace a gathered value, returning the previous value
+    pub fn set<I: Into<Index>>(&mut self, index: I, value: V) -> Result<V, MatricalError> {
+        Ok(std::mem::replace(self.get_mut(index)?, value))
+    }
+
+    // Call `f` with every gathered value and its Gather-relative index, in row-major order
+    pub fn for_each_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Index, &mut V),
+    {
+        let mut view = self.lens.view_mut();
+        for index in self.shape.region() {
+            f(index, &mut view[(self.rows[index.row()], self.cols[index.col()])]);
+        }
+    }
+
+    // Overwrite every gathered value with `value`
+    pub fn fill(&mut self, value: V)
+    where
+        V: Clone,
+    {
+        self.for_each_mut(|_, cell| *cell = value.clone());
+    }
+
+    // Modify every gathered value in place
+    pub fn map_inplace<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut V),
+    {
+        self.for_each_mut(|_, cell| f(cell));
+    }
+
+    // Scatter the values of a same-shaped Lens into the gathered cells
+    pub fn assign_from(&mut self, source: &Lens<'_, V>) -> Result<(), MatricalError>
+    where
+        V: Clone,
+    {
+        if source.shape() != self.shape {
+            return Err(MatricalError::ShapeMismatch {
+                operation: "assign_from",
+                expected: self.shape,
+                actual: source.shape(),
+            });
+        }
+        let source = source.view();
+        self.for_each_mut(|index, cell| *cell = source[(index.row(), index.col())].clone());
+        Ok(())
+    }
+
+    fn source_index(&self, index: Index) -> Result<Index, MatricalError> {
+        let index = self.shape.check_index(index)?;
+        Ok(Index::new(self.rows[index.row()], self.cols[index.col()]))
+    }
+}
+
+impl Selector for Mask {
+    fn contains(&self, _shape: Shape, index: Index) -> bool {
+        self.get(index).unwrap_or(false)
+    }
+}
+
+impl<'a, V> Lens<'a, V> {
+    // Gather an arbitrary list of rows, keeping every column
+    pub fn select_rows(&self, rows: &[usize]) -> Result<Gather<'a, V>, MatricalError> {
+        check_rows(rows, self.shape(), false)?;
+        Gather::new(self.reborrow(), rows.to_vec(), (0..self.cols()).collect())
+    }
+
+    // Gather an arbitrary list of columns, keeping every row
+    pub fn select_cols(&self, cols: &[usize]) -> Result<Gather<'a, V>, MatricalError> {
+        check_cols(cols, self.shape(), false)?;
+        Gather::new(self.reborrow(), (0..self.rows()).collect(), cols.to_vec())
+    }
+
+    // Select the cells set in a Mask of the same shape as this Lens
+    pub fn masked<'m>(&self, mask: &'m Mask) -> Result<Selection<'a, V, &'m Mask>, MatricalError> {
+        check_mask(mask, self.shape())?;
+        self.select(mask)
+    }
+}
+
+impl<'a, V> LensMut<'a, V> {
+    // Mutably gather a list of distinct rows, keeping every column
+    pub fn select_rows_mut(&mut self, rows: &[usize]) -> Result<GatherMut<'_, V>, MatricalError> {
+        check_rows(rows, self.shape(), true)?;
+        let cols = (0..self.cols()).collect();
+        GatherMut::new(self.reborrow(), rows.to_vec(), cols)
+    }
+
+    // Mutably gather a list of distinct columns, keeping every row
+    pub fn select_cols_mut(&mut self, cols: &[usize]) -> Result<GatherMut<'_, V>, MatricalError> {
+        check_cols(cols, self.shape(), true)?;
+        let rows = (0..self.rows()).collect();
+        GatherMut::new(self.reborrow(), rows, cols.to_vec())
+    }
+
+    // Mutably select the cells set in a Mask of the same shape as this LensMut
+    pub fn masked_mut<'m>(&mut self, mask: &'m Mask) -> Result<SelectionMut<'_, V, &'m Mask>, MatricalError> {
+        check_mask(mask, self.shape())?;
+        self.select_mut(mask)
+    }
+}
+
+impl<'a, V> GatherMut<'a, V> {
+    fn new(lens: LensMut<'a, V>, rows: Vec<usize>, cols: Vec<usize>) -> Result<Self, MatricalError> {
+        let shape = Shape::new(rows.len(), cols.len())?;
+        Ok(Self { lens, rows, cols, shape })
+    }
+}
+
+fn check_rows(rows: &[usize], shape: Shape, distinct: bool) -> Result<(), MatricalError> {
+    for &row in rows {
+        if row >= shape.rows() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(row, 0),
+                shape,
+            });
+        }
+    }
+    if distinct {
+        check_distinct(rows, shape.rows(), "select_rows_mut")?;
+    }
+    Ok(())
+}
+
+fn check_cols(cols: &[usize], shape: Shape, distinct: bool) -> Result<(), MatricalError> {
+    for &col in cols {
+        if col >= shape.cols() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(0, col),
+                shape,
+            });
+        }
+    }
+    if distinct {
+        check_distinct(cols, shape.cols(), "select_cols_mut")?;
+    }
+    Ok(())
+}
+
+// Reject repeated indices; every index is already known to be below `len`
+fn check_distinct(indices: &[usize], len: usize, operation: &'static str) -> Result<(), MatricalError> {
+    let mut seen = vec![false; len];
+    for &index in indices {
+        if std::mem::replace(&mut seen[index], true) {
+            return Err(MatricalError::DuplicateIndex { operation, index });
+        }
+    }
+    Ok(())
+}
+
+fn check_mask(mask: &Mask, shape: Shape) -> Result<(), MatricalError> {
+    if mask.shape() != shape {
+        return Err(MatricalError::ShapeMismatch {
+            operation: "masked",
+            expected: shape,
+            actual: mask.shape(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::shape::Region;
+    use crate::strategies::tag::Tag;
+
+    fn matrix() -> Matrix<i32> {
+        Matrix::from_fn((4, 3), |row, col| (row * 10 + col) as i32).unwrap()
+    }
+
+    #[test]
+    fn test_select_rows_keeps_order_and_repeats() {
+        let matrix = matrix();
+        let gather = matrix.as_lens().select_rows(&[3, 0, 3]).unwrap();
+        assert_eq!(gather.shape(), Shape::new(3, 3).unwrap());
+        assert_eq!(
+            gather.iter().copied().collect::<Vec<_>>(),
+            vec![30, 31, 32, 0, 1, 2, 30, 31, 32]
+        );
+        assert_eq!(*gather.get((1, 2)).unwrap(), 2);
+        assert_eq!(gather.source_index((2, 1)).unwrap(), Index::new(3, 1));
+    }
+
+    #[test]
+    fn test_select_cols_then_rows() {
+        let matrix = matrix();
+        let gather = matrix.as_lens().select_cols(&[2, 0]).unwrap().select_rows(&[1, 2]).unwrap();
+        assert_eq!(gather.to_matrix(), Matrix::from_shape_vec((2, 2), vec![12, 10, 22, 20]).unwrap());
+    }
+
+    #[test]
+    fn test_select_validates_up_front() {
+        let matrix = matrix();
+        let lens = matrix.as_lens();
+        assert!(matches!(
+            lens.select_rows(&[0, 4]),
+            Err(MatricalError::IndexOutOfBounds { index, .. }) if index == Index::new(4, 0)
+        ));
+        assert!(matches!(lens.select_cols(&[3]), Err(MatricalError::IndexOutOfBounds { .. })));
+        assert!(lens.select_rows(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_masked() {
+        let matrix = matrix();
+        let mask = Mask::from_fn(matrix.shape(), |row, col| (row + col) % 2 == 0);
+        let selection = matrix.as_lens().masked(&mask).unwrap();
+        assert_eq!(selection.to_vec(), vec![0, 2, 11, 20, 22, 31]);
+
+        let wrong = Mask::trues(Shape::new(3, 3).unwrap());
+        assert!(matches!(
+            matrix.as_lens().masked(&wrong),
+            Err(MatricalError::ShapeMismatch { operation: "masked", .. })
+        ));
+    }
+
+    #[test]
+    fn test_gather_mut_scatters_writes() {
+        let mut matrix = matrix();
+        let source = Matrix::from_shape_vec((2, 3), vec![-1, -2, -3, -4, -5, -6]).unwrap();
+        let mut lens = matrix.as_lens_mut();
+        lens.select_rows_mut(&[2, 0]).unwrap().assign_from(&source.as_lens()).unwrap();
+        let mut cols = lens.select_cols_mut(&[1]).unwrap();
+        cols.map_inplace(|value| *value *= 100);
+        assert_eq!(cols.set((3, 0), 7).unwrap(), 3100);
+
+        assert_eq!(
+            matrix.iter().copied().collect::<Vec<_>>(),
+            vec![-4, -500, -6, 10, 1100, 12, -1, -200, -3, 30, 7, 32]
+        );
+    }
+
+    #[test]
+    fn test_to_matrix_carries_tags() {
+        let mut matrix = matrix();
+        matrix.row_tags_mut(3).unwrap().insert(Tag::custom("session", "b").unwrap()).unwrap();
+        matrix.col_tags_mut(2).unwrap().insert(Tag::unit("ms").unwrap()).unwrap();
+
+        let region = Region::new(Index::new(1, 1), Index::new(4, 3)).unwrap();
+        let lens = matrix.lens(region).unwrap();
+        let gathered = lens.select_rows(&[2, 0]).unwrap().select_cols(&[1]).unwrap().to_matrix();
+        assert_eq!(gathered.row_tags(0).unwrap(), matrix.row_tags(3).unwrap());
+        assert!(gathered.row_tags(1).unwrap().is_empty());
+        assert_eq!(gathered.col_tags(0).unwrap(), matrix.col_tags(2).unwrap());
+    }
+
+    #[test]
+    fn test_gather_mut_rejects_duplicates() {
+        let mut matrix = matrix();
+        let mut lens = matrix.as_lens_mut();
+        assert!(matches!(
+            lens.select_rows_mut(&[1, 2, 1]),
+            Err(MatricalError::DuplicateIndex { index: 1, .. })
+        ));
+        assert!(matches!(lens.select_cols_mut(&[5]), Err(MatricalError::IndexOutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_masked_mut() {
+        let mut matrix = matrix();
+        let mask = Mask::from_fn(matrix.shape(), |_, col| col == 1);
+        matrix.as_lens_mut().masked_mut(&mask).unwrap().fill(0);
+        assert_eq!(matrix.iter().filter(|value| **value == 0).count(), 5);
+        assert_eq!(*matrix.get((3, 1)).unwrap(), 0);
+    }
+}
@@ -1,8 +1,13 @@
-
-
 use crate::schematics::Element;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::Region;
+use crate::strategies::cog::{Cog, NoPolicy};
+use crate::strategies::lens::{Lens, LensMut};
+use crate::strategies::pipeline::{FanOut, Then};
+use crate::strategies::report::{ExecutionReport, GearOutput, OperationId, Outcome, StageReport};
 use crate::error::MatricalError;
-use ndarray::{Array2, s};
+
+use std::ops::AddAssign;
 
 
 
@@ -22,194 +27,344 @@ where
 
 
 
-// The Gear struct
-#[derive(Debug, Clone)]
-pub struct Gear {
-    // The top left and bottom right coordinates of the sub-matrix
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
-    // The data contained in the gear
-    data: Array2<f64>,
+// The Gear trait
+//
+// A Gear is a read-only transformation of the values visible through a Lens.
+// It can only see the Lens it is given, and whatever it computes is returned
+// as `Output` rather than written back, so its signature alone shows that the
+// Matrix is left untouched. Gears are ordinary traits: downstream crates
+// implement them for their own types and call them with static dispatch.
+// `Policy` is the Cog the Gear needs; Gears without one use `NoPolicy`.
+// `operation` names the Gear in ExecutionReports; it has no default, so every
+// Gear picks a name that survives refactors and compiler upgrades.
+// `apply_staged` records the stages a Gear runs; only pipelines, which run
+// several Gears, override it.
+//
+pub trait Gear<V> {
+    type Output;
+    type Policy: Cog;
+
+    fn apply(&self, input: &Lens<'_, V>, policy: &Self::Policy) -> Result<Self::Output, MatricalError>;
+
+    fn operation(&self) -> OperationId;
+
+    fn apply_staged(
+        &self,
+        input: &Lens<'_, V>,
+        policy: &Self::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<Self::Output, MatricalError>
+    where
+        Self::Output: GearOutput,
+    {
+        let result = self.apply(input, policy);
+        stages.push(StageReport::gear(self.operation(), policy, input.shape(), &result));
+        result
+    }
+
+    // Feed the output of this Gear into `next`
+    fn then<B>(self, next: B) -> Then<Self, B>
+    where
+        Self: Sized,
+    {
+        Then::new(self, next)
+    }
+
+    // Apply this Gear and `other` to the same input
+    fn fan_out<B>(self, other: B) -> FanOut<Self, B>
+    where
+        Self: Sized,
+    {
+        FanOut::new(self, other)
+    }
 }
 
-impl Gear {
-    // Create a new Gear with the given coordinates and data
-    pub fn new(
-        top_left: (usize, usize),
-        bottom_right: (usize, usize),
-        data: Array2<f64>,
-    ) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-            data,
-        }
+// The GearMut trait
+//
+// A GearMut writes its result in place through a LensMut. The exclusive
+// borrow keeps every write inside the Lens bounds, and nothing else can
+// observe the region until the GearMut returns.
+//
+pub trait GearMut<V> {
+    type Policy: Cog;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &Self::Policy) -> Result<(), MatricalError>;
+
+    fn operation(&self) -> OperationId;
+
+    fn apply_mut_staged(
+        &self,
+        target: &mut LensMut<'_, V>,
+        policy: &Self::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<(), MatricalError> {
+        let result = self.apply_mut(target, policy);
+        stages.push(StageReport::gear_mut(self.operation(), policy, target.shape(), &result));
+        result
+    }
+
+    // Apply `next` to the same target once this GearMut is done
+    fn then<B>(self, next: B) -> Then<Self, B>
+    where
+        Self: Sized,
+    {
+        Then::new(self, next)
     }
 }
 
-// Gear Operation
-pub trait GearOperation {
-    fn apply(&self, gear: &mut Gear, context: &GearContext) -> Result<(), MatricalError>;
+impl<V, G: Gear<V> + ?Sized> Gear<V> for &G {
+    type Output = G::Output;
+    type Policy = G::Policy;
+
+    fn apply(&self, input: &Lens<'_, V>, policy: &G::Policy) -> Result<Self::Output, MatricalError> {
+        (**self).apply(input, policy)
+    }
+
+    fn operation(&self) -> OperationId {
+        (**self).operation()
+    }
+
+    fn apply_staged(
+        &self,
+        input: &Lens<'_, V>,
+        policy: &G::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<Self::Output, MatricalError>
+    where
+        Self::Output: GearOutput,
+    {
+        (**self).apply_staged(input, policy, stages)
+    }
 }
 
-// Gear Strategy
-pub trait GearStrategy {
-    fn execute(
+impl<V, G: GearMut<V> + ?Sized> GearMut<V> for &G {
+    type Policy = G::Policy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &G::Policy) -> Result<(), MatricalError> {
+        (**self).apply_mut(target, policy)
+    }
+
+    fn operation(&self) -> OperationId {
+        (**self).operation()
+    }
+
+    fn apply_mut_staged(
         &self,
-        gear: &Gear,
-        index: Option<(usize, usize)>,
-        other: Option<bool>,
-    ) -> Result<(), MatricalError>;
+        target: &mut LensMut<'_, V>,
+        policy: &G::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<(), MatricalError> {
+        (**self).apply_mut_staged(target, policy, stages)
+    }
 }
 
 // The GearContext struct
+//
+// Binds a Gear to a Region of a Matrix: the Region is validated against the
+// Matrix and the Gear only ever sees the Lens over that Region.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GearContext {
-    // The top left and bottom right coordinates of the sub-matrix
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
+    // The region of the sub-matrix
+    region: Region,
 }
 
 impl GearContext {
-    // Create a new GearContext with the given coordinates
-    pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-        }
+    // Create a new GearContext with the given region
+    pub fn new(region: Region) -> Self {
+        Self { region }
+    }
+
+    // Get the region the Gear is applied to
+    pub fn region(&self) -> Region {
+        self.region
     }
-}
 
-// The GearFactory trait
-pub trait GearFactory {
-    fn create(&self, top_left: (usize, usize), bottom_right: (usize, usize)) -> Gear;
+    // Apply a read-only Gear to the region of `matrix`, reporting what was done
+    pub fn apply<V, G>(
+        &self,
+        gear: &G,
+        policy: &G::Policy,
+        matrix: &Matrix<V>,
+    ) -> (Result<G::Output, MatricalError>, ExecutionReport)
+    where
+        G: Gear<V>,
+        G::Output: GearOutput,
+    {
+        let mut stages = Vec::new();
+        let result = matrix
+            .lens(self.region)
+            .and_then(|lens| gear.apply_staged(&lens, policy, &mut stages));
+        let output_shape = result.as_ref().ok().and_then(GearOutput::output_shape);
+        let report = ExecutionReport::new(
+            gear.operation(),
+            self.region,
+            matrix.tags(),
+            policy,
+            output_shape,
+            Outcome::of(&result),
+            stages,
+        );
+        (result, report)
+    }
+
+    // Apply a mutating Gear to the region of `matrix`, reporting what was done
+    pub fn apply_mut<V, G: GearMut<V>>(
+        &self,
+        gear: &G,
+        policy: &G::Policy,
+        matrix: &mut Matrix<V>,
+    ) -> (Result<(), MatricalError>, ExecutionReport) {
+        let mut stages = Vec::new();
+        let result = matrix
+            .lens_mut(self.region)
+            .and_then(|mut lens| gear.apply_mut_staged(&mut lens, policy, &mut stages));
+        let output_shape = result.as_ref().ok().map(|_| self.region.shape());
+        let report = ExecutionReport::new(
+            gear.operation(),
+            self.region,
+            matrix.tags(),
+            policy,
+            output_shape,
+            Outcome::of(&result),
+            stages,
+        );
+        (result, report)
+    }
 }
 
-// The GearFactoryImpl struct
-pub struct GearFactoryImpl {
-    // The top left and bottom right coordinates of the sub-matrix
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
-    // The data contained in the gear
-    data: Array2<f64>,
+// The AddConstant struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AddConstant<V> {
+    // The constant to add to every value
+    constant: V,
 }
 
-impl GearFactoryImpl {
-    // Create a new GearFactoryImpl with the given coordinates
-    pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-            data: Array2::zeros((0, 0)),
-        }
+impl<V> AddConstant<V> {
+    // Create a new AddConstant with the given constant
+    pub fn new(constant: V) -> Self {
+        Self { constant }
     }
+}
 
-    // Set the data of the GearFactoryImpl
-    pub fn data(mut self, data: Array2<f64>) -> Self {
-        self.data = data;
-        self
+impl<V: AddAssign + Clone> GearMut<V> for AddConstant<V> {
+    type Policy = NoPolicy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        target.map_inplace(|value| *value += self.constant.clone());
+        Ok(())
     }
-}
 
-impl GearFactory for GearFactoryImpl {
-    fn create(&self, top_left: (usize, usize), bottom_right: (usize, usize)) -> Gear {
-        Gear::new(top_left, bottom_right, self.data.clone())
+    fn operation(&self) -> OperationId {
+        OperationId::new("add_constant", 1)
     }
 }
 
-// The GearOperationImpl struct
-pub struct GearOperationImpl {
-    // The constant to add to the sub-matrix
-    constant: f64,
+// The Fill struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fill<V> {
+    // The value to write into every cell
+    value: V,
 }
 
-impl GearOperationImpl {
-    // Create a new GearOperation with the given constant
-    pub fn new(constant: f64) -> Self {
-        Self { constant }
+impl<V> Fill<V> {
+    // Create a new Fill with the given value
+    pub fn new(value: V) -> Self {
+        Self { value }
     }
 }
 
-impl GearOperation for GearOperationImpl {
-    fn apply(&self, gear: &mut Gear, context: &GearContext) -> Result<(), MatricalError> {
-        // Check if the context is within the gear dimensions
-        if context.top_left.0 >= gear.data.dim().0
-            || context.top_left.1 >= gear.data.dim().1
-            || context.bottom_right.0 >= gear.data.dim().0
-            || context.bottom_right.1 >= gear.data.dim().1
-        {
-            return Err(MatricalError::IndexOutOfBounds);
-        }
-
-        let (start_row, start_col) = context.top_left;
-        let (end_row, end_col) = context.bottom_right;
-
-        let data = &mut gear.data.slice_mut(s![start_row..=end_row, start_col..=end_col]);
-        *data += self.constant;
+impl<V: Clone> GearMut<V> for Fill<V> {
+    type Policy = NoPolicy;
 
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        target.fill(self.value.clone());
         Ok(())
     }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("fill", 1)
+    }
 }
 
-// The GearStrategyImpl struct
-pub struct GearStrategyImpl {
-    // The top left and bottom right coordinates of the sub-matrix
-    top_left: (usize, usize),
-    bottom_right: (usize, usize),
+// The Map struct, applying a function to every value in place
+#[derive(Debug, Clone, Copy)]
+pub struct Map<F> {
+    function: F,
 }
 
-impl GearStrategyImpl {
-    // Create a new GearStrategyImpl with the given coordinates
-    pub fn new(top_left: (usize, usize), bottom_right: (usize, usize)) -> Self {
-        Self {
-            top_left,
-            bottom_right,
-        }
+impl<F> Map<F> {
+    // Create a new Map from the given function
+    pub fn new(function: F) -> Self {
+        Self { function }
     }
 }
 
-impl GearStrategy for GearStrategyImpl {
-    fn execute(
-        &self,
-        gear: &Gear,
-        index: Option<(usize, usize)>,
-        other: Option<bool>,
-    ) -> Result<(), MatricalError> {
-        // Check if the coordinates are within the matrix dimensions
-        if self.top_left.0 >= gear.data.dim().0
-            || self.top_left.1 >= gear.data.dim().1
-            || self.bottom_right.0 >= gear.data.dim().0
-            || self.bottom_right.1 >= gear.data.dim().1
-        {
-            return Err(MatricalError::IndexOutOfBounds);
-        }
+impl<V: Clone, F: Fn(V) -> V> GearMut<V> for Map<F> {
+    type Policy = NoPolicy;
 
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        target.map_inplace(|value| *value = (self.function)(value.clone()));
         Ok(())
     }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("map", 1)
+    }
+}
+
+// The Collect struct, copying the values visible through a Lens into a Matrix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Collect;
+
+impl<V: Clone> Gear<V> for Collect {
+    type Output = Matrix<V>;
+    type Policy = NoPolicy;
+
+    fn apply(&self, input: &Lens<'_, V>, _policy: &NoPolicy) -> Result<Matrix<V>, MatricalError> {
+        Ok(input.to_matrix())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("collect", 1)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schematics::shape::Index;
 
-    #[test]
-    fn test_gear_operation_apply() {
-        // Create a new gear
-        let gear_data = Array2::zeros((5, 5));
-        let mut gear = Gear::new((0, 0), (2, 2), gear_data);
+    fn corners(top_left: (usize, usize), bottom_right: (usize, usize)) -> Region {
+        Region::inclusive(top_left.into(), bottom_right.into()).unwrap()
+    }
 
-        // Create a new gear operation
-        let operation = GearOperationImpl::new(1.0);
+    // A downstream-style Gear defined outside the library's built-ins
+    struct Total;
 
-        // Create a new gear context
-        let context = GearContext::new((0, 0), (2, 2));
+    impl Gear<f64> for Total {
+        type Output = f64;
+        type Policy = NoPolicy;
 
-        // Apply the operation to the gear
-        let result = operation.apply(&mut gear, &context);
+        fn apply(&self, input: &Lens<'_, f64>, _policy: &NoPolicy) -> Result<f64, MatricalError> {
+            Ok(input.iter().sum())
+        }
 
-        assert!(result.is_ok());
+        fn operation(&self) -> OperationId {
+            OperationId::new("total", 1)
+        }
+    }
+
+    #[test]
+    fn test_gear_mut_apply() {
+        let mut matrix: Matrix<f64> = Matrix::zeros((5, 5)).unwrap();
 
-        // Check the updated gear data
-        let expected_data = Array2::from_shape_vec(
+        // Apply the gear to the top left 3x3 region
+        let context = GearContext::new(corners((0, 0), (2, 2)));
+        context.apply_mut(&AddConstant::new(1.0), &NoPolicy, &mut matrix).0.unwrap();
+
+        // Check the updated matrix data
+        let expected = Matrix::from_shape_vec(
             (5, 5),
             vec![
                 1.0, 1.0, 1.0, 0.0, 0.0, //
@@ -221,74 +376,52 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(gear.data, expected_data);
+        assert_eq!(matrix, expected);
     }
 
     #[test]
-    fn test_basics() {
-        use super::*;
-        
-        // Create a new gear
-        let mut gear = Gear::new((0, 0), (2, 2), Array2::zeros((3, 3)));
-    
-        // Create a new gear operation
-        let operation = GearOperationImpl::new(1.0);
-    
-        // Create a new gear context
-        let context = GearContext::new((0, 0), (2, 2));
-    
-        // Apply the operation to the gear
-        match operation.apply(&mut gear, &context) {
-            Ok(()) => println!("Operation applied successfully"),
-            Err(err) => println!("Failed to apply operation: {:?}", err),
-        }
-    
-        // Print the gear data
-        println!("{:?}", gear.data);
+    fn test_gear_reads_only_its_lens() {
+        let matrix = Matrix::from_fn((3, 3), |row, col| (row * 3 + col) as f64).unwrap();
+
+        let context = GearContext::new(corners((1, 1), (2, 2)));
+        assert_eq!(context.apply(&Total, &NoPolicy, &matrix).0.unwrap(), 4.0 + 5.0 + 7.0 + 8.0);
+        assert_eq!(Total.apply(&matrix.as_lens(), &NoPolicy).unwrap(), 36.0);
     }
 
     #[test]
-    fn test_gear_factory() {
-        let top_left = (0, 0);
-        let bottom_right = (2, 2);
-        let data = Array2::zeros((3, 3));
+    fn test_gears_on_lenses() {
+        let mut matrix: Matrix<i32> = Matrix::zeros((2, 4)).unwrap();
+        let (mut left, mut right) = matrix.as_lens_mut().split_at_col(2).unwrap();
 
-        let gear_factory = GearFactoryImpl::new(top_left, bottom_right).data(data.clone());
-        let gear = gear_factory.create(top_left, bottom_right);
+        Fill::new(3).apply_mut(&mut left, &NoPolicy).unwrap();
+        Map::new(|value: i32| value - 1).apply_mut(&mut right, &NoPolicy).unwrap();
+        AddConstant::new(10).apply_mut(&mut right, &NoPolicy).unwrap();
 
-        assert_eq!(gear.top_left, top_left);
-        assert_eq!(gear.bottom_right, bottom_right);
-        assert_eq!(gear.data, data);
+        let collected = Collect.apply(&matrix.as_lens(), &NoPolicy).unwrap();
+        assert_eq!(collected.iter().copied().collect::<Vec<_>>(), vec![3, 3, 9, 9, 3, 3, 9, 9]);
     }
 
     #[test]
-    fn test_gear_operation() {
-        let top_left = (0, 0);
-        let bottom_right = (2, 2);
-        let data = Array2::zeros((3, 3));
-
-        let mut gear = Gear::new(top_left, bottom_right, data);
-        let operation = GearOperationImpl::new(1.0);
-        let context = GearContext::new(top_left, bottom_right);
-
-        operation.apply(&mut gear, &context).unwrap();
-
-        for i in 0..3 {
-            for j in 0..3 {
-                assert_eq!(gear.data[[i, j]], 1.0);
-            }
+    fn test_gear_by_reference() {
+        fn total<G: Gear<f64, Output = f64, Policy = NoPolicy>>(gear: G, matrix: &Matrix<f64>) -> f64 {
+            gear.apply(&matrix.as_lens(), &NoPolicy).unwrap()
         }
+
+        let matrix = Matrix::from_shape_vec((1, 2), vec![1.5, 2.5]).unwrap();
+        assert_eq!(total(&Total, &matrix), 4.0);
     }
 
     #[test]
-    fn test_gear_strategy() {
-        let top_left = (0, 0);
-        let bottom_right = (2, 2);
-        let data = Array2::zeros((3, 3));
-
-        let gear = Gear::new(top_left, bottom_right, data);
-        let strategy = GearStrategyImpl::new(top_left, bottom_right);
-
-        strategy.execute(&gear, None, None).unwrap();
+    fn test_gear_out_of_bounds() {
+        let mut matrix: Matrix<f64> = Matrix::zeros((3, 3)).unwrap();
+        let region = Region::new(Index::new(1, 1), Index::new(4, 3)).unwrap();
+
+        let context = GearContext::new(region);
+        assert!(matches!(
+            context.apply_mut(&AddConstant::new(1.0), &NoPolicy, &mut matrix).0,
+            Err(MatricalError::RegionOutOfBounds { .. })
+        ));
+        assert!(context.apply(&Collect, &NoPolicy, &matrix).0.is_err());
+        assert!(matrix.iter().all(|value| *value == 0.0));
     }
-}
\ No newline at end of file
+}
@@ -19,32 +19,495 @@ SparseLens: This lens would focus on the non-zero elements of the matrix. This c
  */
 
 use crate::error::MatricalError;
-use crossbeam::queue::{ArrayQueue, SegQueue};
+use crate::schematics::matrix::{Matrix, MatrixContext};
+use crate::schematics::shape::{Index, Region, Shape};
+use crate::strategies::tag::TagSet;
+use ndarray::{s, ArrayView2, ArrayViewMut2, Axis, IndexLonger};
 use std::marker::PhantomData;
 
 
-pub trait Lens<V> {
-    fn execute(&self, lens: &mut dyn Lens<V>) -> Result<(), MatricalError>;
+// An immutable rectangular view over part of a Matrix
+//
+// A Lens borrows the selected elements directly from the Matrix storage; no
+// element is copied when a Lens, a nested Lens, or a row or column of a Lens
+// is created. The `'a` lifetime ties the Lens to the Matrix, so it can never
+// outlive the data it looks at. All indices passed to a Lens are relative to
+// its own top left corner, and the Lens shares the Tags of the Matrix so row
+// and column Tags stay reachable through any selection.
+//
+#[derive(Debug, Clone)]
+pub struct Lens<'a, V> {
+    view: ArrayView2<'a, V>,
+    // The selected region in the coordinates of the owning Matrix
+    region: Region,
+    context: &'a MatrixContext,
 }
 
-pub trait MatrixLensTrait {
-    fn is_valid(&self) -> bool;
+// A Lens over exactly one row
+pub type RowLens<'a, V> = Lens<'a, V>;
+
+// A Lens over exactly one column
+pub type ColumnLens<'a, V> = Lens<'a, V>;
+
+impl<'a, V> Lens<'a, V> {
+    pub(crate) fn new(view: ArrayView2<'a, V>, region: Region, context: &'a MatrixContext) -> Self {
+        Self { view, region, context }
+    }
+
+    // Copy the Lens handle without requiring `V: Clone`
+    pub(crate) fn reborrow(&self) -> Lens<'a, V> {
+        Lens::new(self.view, self.region, self.context)
+    }
+
+    // Get the selected region in the coordinates of the owning Matrix
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    // Get the shape of the selection
+    pub fn shape(&self) -> Shape {
+        self.region.shape()
+    }
+
+    // Get the number of selected rows
+    pub fn rows(&self) -> usize {
+        self.region.rows()
+    }
+
+    // Get the number of selected columns
+    pub fn cols(&self) -> usize {
+        self.region.cols()
+    }
+
+    // Get the number of selected elements
+    pub fn len(&self) -> usize {
+        self.shape().len()
+    }
+
+    // Check whether the Lens selects nothing
+    pub fn is_empty(&self) -> bool {
+        self.region.is_empty()
+    }
+
+    // Get a reference to the value at a Lens-relative index
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<&'a V, MatricalError> {
+        let index = self.shape().check_index(index.into())?;
+        Ok(IndexLonger::index(&self.view, (index.row(), index.col())))
+    }
+
+    // Create a nested Lens from a region relative to this Lens
+    pub fn lens(&self, region: Region) -> Result<Lens<'a, V>, MatricalError> {
+        let region = self.shape().check_region(region)?;
+        let view = self.view.slice_move(s![region.row_range(), region.col_range()]);
+        Ok(Lens::new(view, region.offset(self.region.start())?, self.context))
+    }
+
+    // Create a Lens over one row of this Lens
+    pub fn row(&self, row: usize) -> Result<RowLens<'a, V>, MatricalError> {
+        if row >= self.rows() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(row, 0),
+                shape: self.shape(),
+            });
+        }
+        self.lens(Region::new(Index::new(row, 0), Index::new(row + 1, self.cols()))?)
+    }
+
+    // Create a Lens over one column of this Lens
+    pub fn col(&self, col: usize) -> Result<ColumnLens<'a, V>, MatricalError> {
+        if col >= self.cols() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(0, col),
+                shape: self.shape(),
+            });
+        }
+        self.lens(Region::new(Index::new(0, col), Index::new(self.rows(), col + 1))?)
+    }
+
+    // Iterate over the rows of this Lens, top to bottom
+    pub fn iter_rows(&self) -> impl Iterator<Item = RowLens<'a, V>> + '_ {
+        (0..self.rows()).filter_map(move |row| self.row(row).ok())
+    }
+
+    // Iterate over the columns of this Lens, left to right
+    pub fn iter_cols(&self) -> impl Iterator<Item = ColumnLens<'a, V>> + '_ {
+        (0..self.cols()).filter_map(move |col| self.col(col).ok())
+    }
+
+    // Iterate over the selected values in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &'a V> {
+        self.view.into_iter()
+    }
+
+    // Iterate over the selected values with their Lens-relative index
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Index, &'a V)> {
+        self.shape().region().iter().zip(self.iter())
+    }
+
+    // Borrow the selection as an ndarray view
+    pub fn view(&self) -> ArrayView2<'a, V> {
+        self.view
+    }
+
+    // Get the Tags applied to the whole Matrix
+    pub fn tags(&self) -> &'a TagSet {
+        self.context.tags()
+    }
+
+    // Get the Tags applied to a Lens-relative row
+    pub fn row_tags(&self, row: usize) -> Result<&'a TagSet, MatricalError> {
+        row_tags(self.context, self.region, row)
+    }
+
+    // Get the Tags applied to a Lens-relative column
+    pub fn col_tags(&self, col: usize) -> Result<&'a TagSet, MatricalError> {
+        col_tags(self.context, self.region, col)
+    }
+
+    // Copy the selected values into a new Matrix, keeping the Matrix Tags and
+    // the Tags of the selected rows and columns
+    pub fn to_matrix(&self) -> Matrix<V>
+    where
+        V: Clone,
+    {
+        let context = self.context.select(self.region.row_range(), self.region.col_range());
+        Matrix::from_parts(self.view.to_owned(), context)
+    }
+
+    pub(crate) fn context(&self) -> &'a MatrixContext {
+        self.context
+    }
 }
 
-pub struct MatrixLens<T> {
-    data: T,
+// A mutable rectangular view over part of a Matrix
+//
+// A LensMut holds an exclusive borrow of the selected elements for its whole
+// lifetime, so no other Lens or LensMut can observe the region while it is
+// being written. Splitting a LensMut (by row, by column, or into several
+// disjoint regions) hands out non-overlapping exclusive borrows without any
+// unsafe code in the caller.
+//
+#[derive(Debug)]
+pub struct LensMut<'a, V> {
+    view: ArrayViewMut2<'a, V>,
+    // The selected region in the coordinates of the owning Matrix
+    region: Region,
+    context: &'a MatrixContext,
 }
 
-impl<T> MatrixLens<T> {
-    pub fn new(data: T) -> Self {
-        Self { data }
+impl<'a, V> LensMut<'a, V> {
+    pub(crate) fn new(view: ArrayViewMut2<'a, V>, region: Region, context: &'a MatrixContext) -> Self {
+        Self { view, region, context }
+    }
+
+    // Get the selected region in the coordinates of the owning Matrix
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    // Get the shape of the selection
+    pub fn shape(&self) -> Shape {
+        self.region.shape()
+    }
+
+    // Get the number of selected rows
+    pub fn rows(&self) -> usize {
+        self.region.rows()
+    }
+
+    // Get the number of selected columns
+    pub fn cols(&self) -> usize {
+        self.region.cols()
+    }
+
+    // Get the number of selected elements
+    pub fn len(&self) -> usize {
+        self.shape().len()
+    }
+
+    // Check whether the LensMut selects nothing
+    pub fn is_empty(&self) -> bool {
+        self.region.is_empty()
+    }
+
+    // Get a reference to the value at a Lens-relative index
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<&V, MatricalError> {
+        let index = self.shape().check_index(index.into())?;
+        Ok(&self.view[(index.row(), index.col())])
+    }
+
+    // Get a mutable reference to the value at a Lens-relative index
+    pub fn get_mut<I: Into<Index>>(&mut self, index: I) -> Result<&mut V, MatricalError> {
+        let index = self.shape().check_index(index.into())?;
+        Ok(&mut self.view[(index.row(), index.col())])
+    }
+
+    // Replace the value at a Lens-relative index, returning the previous value
+    pub fn set<I: Into<Index>>(&mut self, index: I, value: V) -> Result<V, MatricalError> {
+        Ok(std::mem::replace(self.get_mut(index)?, value))
+    }
+
+    // Borrow the selection immutably
+    pub fn as_lens(&self) -> Lens<'_, V> {
+        Lens::new(self.view.view(), self.region, self.context)
+    }
+
+    // Reborrow the whole LensMut for a shorter lifetime
+    pub fn reborrow(&mut self) -> LensMut<'_, V> {
+        LensMut::new(self.view.view_mut(), self.region, self.context)
+    }
+
+    // Reborrow a nested region, relative to this LensMut, mutably
+    pub fn lens_mut(&mut self, region: Region) -> Result<LensMut<'_, V>, MatricalError> {
+        let region = self.shape().check_region(region)?;
+        let view = self.view.slice_mut(s![region.row_range(), region.col_range()]);
+        Ok(LensMut::new(view, region.offset(self.region.start())?, self.context))
+    }
+
+    // Narrow this LensMut to a nested region, keeping the original borrow
+    pub fn into_lens_mut(self, region: Region) -> Result<LensMut<'a, V>, MatricalError> {
+        let region = self.shape().check_region(region)?;
+        let origin = self.region.start();
+        let view = self.view.slice_move(s![region.row_range(), region.col_range()]);
+        Ok(LensMut::new(view, region.offset(origin)?, self.context))
+    }
+
+    // Iterate over the selected values in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &V> {
+        self.view.iter()
+    }
+
+    // Iterate mutably over the selected values in row-major order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.view.iter_mut()
+    }
+
+    // Overwrite every selected value with `value`
+    pub fn fill(&mut self, value: V)
+    where
+        V: Clone,
+    {
+        self.view.fill(value);
+    }
+
+    // Modify every selected value in place
+    pub fn map_inplace<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut V),
+    {
+        self.view.map_inplace(f);
+    }
+
+    // Copy every value of `source` into this LensMut; the shapes must match
+    pub fn assign_from(&mut self, source: &Lens<'_, V>) -> Result<(), MatricalError>
+    where
+        V: Clone,
+    {
+        if source.shape() != self.shape() {
+            return Err(MatricalError::ShapeMismatch {
+                operation: "assign_from",
+                expected: self.shape(),
+                actual: source.shape(),
+            });
+        }
+        self.view.assign(&source.view());
+        Ok(())
+    }
+
+    // Split into the rows above `row` and the rows from `row` down
+    pub fn split_at_row(self, row: usize) -> Result<(LensMut<'a, V>, LensMut<'a, V>), MatricalError> {
+        if row > self.rows() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(row, 0),
+                shape: self.shape(),
+            });
+        }
+        let start = self.region.start();
+        let top = Region::at(start, Shape::new(row, self.cols())?)?;
+        let bottom = Region::new(Index::new(top.end().row(), start.col()), self.region.end())?;
+        let (upper, lower) = self.view.split_at(Axis(0), row);
+        Ok((LensMut::new(upper, top, self.context), LensMut::new(lower, bottom, self.context)))
+    }
+
+    // Split into the columns left of `col` and the columns from `col` right
+    pub fn split_at_col(self, col: usize) -> Result<(LensMut<'a, V>, LensMut<'a, V>), MatricalError> {
+        if col > self.cols() {
+            return Err(MatricalError::IndexOutOfBounds {
+                index: Index::new(0, col),
+                shape: self.shape(),
+            });
+        }
+        let start = self.region.start();
+        let left = Region::at(start, Shape::new(self.rows(), col)?)?;
+        let right = Region::new(Index::new(start.row(), left.end().col()), self.region.end())?;
+        let (first, second) = self.view.split_at(Axis(1), col);
+        Ok((LensMut::new(first, left, self.context), LensMut::new(second, right, self.context)))
+    }
+
+    // Split into one LensMut per region, in the order given
+    //
+    // Regions are relative to this LensMut and must be in bounds and pairwise
+    // disjoint. The borrow is divided by repeatedly cutting along a row or
+    // column that separates the remaining regions, so the regions must also be
+    // separable that way; four regions interlocking in a pinwheel around a
+    // shared centre cannot be split and are rejected as unsupported.
+    pub fn into_disjoint(self, regions: &[Region]) -> Result<Vec<LensMut<'a, V>>, MatricalError> {
+        for (i, first) in regions.iter().enumerate() {
+            self.shape().check_region(*first)?;
+            if let Some(second) = regions[i + 1..].iter().find(|second| first.intersection(second).is_some()) {
+                return Err(MatricalError::OverlappingRegions {
+                    first: *first,
+                    second: *second,
+                });
+            }
+        }
+
+        let mut lenses: Vec<Option<LensMut<'a, V>>> = regions.iter().map(|_| None).collect();
+        let pending: Vec<(usize, Region)> = regions.iter().copied().enumerate().collect();
+        let origin = Origin {
+            index: self.region.start(),
+            context: self.context,
+        };
+        split_disjoint(self.view, Index::new(0, 0), pending, origin, &mut lenses)?;
+        Ok(lenses.into_iter().flatten().collect())
+    }
+
+    // Borrow the selection as a mutable ndarray view
+    pub fn view_mut(&mut self) -> ArrayViewMut2<'_, V> {
+        self.view.view_mut()
+    }
+
+    // Get the Tags applied to the whole Matrix
+    pub fn tags(&self) -> &'a TagSet {
+        self.context.tags()
+    }
+
+    // Get the Tags applied to a Lens-relative row
+    pub fn row_tags(&self, row: usize) -> Result<&'a TagSet, MatricalError> {
+        row_tags(self.context, self.region, row)
+    }
+
+    // Get the Tags applied to a Lens-relative column
+    pub fn col_tags(&self, col: usize) -> Result<&'a TagSet, MatricalError> {
+        col_tags(self.context, self.region, col)
     }
 }
 
-impl<T> MatrixLensTrait for MatrixLens<T> {
-    fn is_valid(&self) -> bool {
-        // Check if the data is valid
-        self.data.is_valid()
+// Look up the Tags of a row relative to `region`
+fn row_tags(context: &MatrixContext, region: Region, row: usize) -> Result<&TagSet, MatricalError> {
+    if row >= region.rows() {
+        return Err(MatricalError::IndexOutOfBounds {
+            index: Index::new(row, 0),
+            shape: region.shape(),
+        });
+    }
+    context.row_tags(region.start().row() + row)
+}
+
+// Look up the Tags of a column relative to `region`
+fn col_tags(context: &MatrixContext, region: Region, col: usize) -> Result<&TagSet, MatricalError> {
+    if col >= region.cols() {
+        return Err(MatricalError::IndexOutOfBounds {
+            index: Index::new(0, col),
+            shape: region.shape(),
+        });
+    }
+    context.col_tags(region.start().col() + col)
+}
+
+// Where the LensMut being split sits in its Matrix
+#[derive(Clone, Copy)]
+struct Origin<'a> {
+    index: Index,
+    context: &'a MatrixContext,
+}
+
+// Hand out `pending` regions (relative to the LensMut being split) from `view`,
+// whose top left corner sits at `offset` in the same coordinates
+fn split_disjoint<'a, V>(
+    view: ArrayViewMut2<'a, V>,
+    offset: Index,
+    pending: Vec<(usize, Region)>,
+    origin: Origin<'a>,
+    lenses: &mut Vec<Option<LensMut<'a, V>>>,
+) -> Result<(), MatricalError> {
+    // Empty regions own no elements and can be carved from anywhere
+    let (empty, pending): (Vec<_>, Vec<_>) = pending.into_iter().partition(|(_, region)| region.is_empty());
+    for (slot, region) in empty {
+        let view = ArrayViewMut2::from_shape((region.rows(), region.cols()), &mut [])
+            .map_err(|_| MatricalError::InvalidShape {
+                rows: region.rows(),
+                cols: region.cols(),
+            })?;
+        lenses[slot] = Some(LensMut::new(view, region.offset(origin.index)?, origin.context));
+    }
+
+    if let [(slot, region)] = pending[..] {
+        let local = Region::new(
+            Index::new(region.start().row() - offset.row(), region.start().col() - offset.col()),
+            Index::new(region.end().row() - offset.row(), region.end().col() - offset.col()),
+        )?;
+        let view = view.slice_move(s![local.row_range(), local.col_range()]);
+        lenses[slot] = Some(LensMut::new(view, region.offset(origin.index)?, origin.context));
+        return Ok(());
+    }
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    for cut in pending.iter().map(|(_, region)| region.end().row()) {
+        let (above, below): (Vec<_>, Vec<_>) = pending.iter().partition(|(_, region)| region.end().row() <= cut);
+        if !below.is_empty() && below.iter().all(|(_, region)| region.start().row() >= cut) {
+            let (upper, lower) = view.split_at(Axis(0), cut - offset.row());
+            split_disjoint(upper, offset, above, origin, lenses)?;
+            return split_disjoint(lower, Index::new(cut, offset.col()), below, origin, lenses);
+        }
+    }
+    for cut in pending.iter().map(|(_, region)| region.end().col()) {
+        let (left, right): (Vec<_>, Vec<_>) = pending.iter().partition(|(_, region)| region.end().col() <= cut);
+        if !right.is_empty() && right.iter().all(|(_, region)| region.start().col() >= cut) {
+            let (first, second) = view.split_at(Axis(1), cut - offset.col());
+            split_disjoint(first, offset, left, origin, lenses)?;
+            return split_disjoint(second, Index::new(offset.row(), cut), right, origin, lenses);
+        }
+    }
+
+    Err(MatricalError::Unsupported { operation: "into_disjoint" })
+}
+
+impl<V> Matrix<V> {
+    // Create a Lens over a region of the Matrix
+    pub fn lens(&self, region: Region) -> Result<Lens<'_, V>, MatricalError> {
+        let region = self.shape().check_region(region)?;
+        let view = self
+            .as_array()
+            .slice(s![region.row_range(), region.col_range()]);
+        Ok(Lens::new(view, region, self.context()))
+    }
+
+    // Create a Lens over the whole Matrix
+    pub fn as_lens(&self) -> Lens<'_, V> {
+        Lens::new(self.as_array().view(), self.shape().region(), self.context())
+    }
+
+    // Create a LensMut over a region of the Matrix
+    pub fn lens_mut(&mut self, region: Region) -> Result<LensMut<'_, V>, MatricalError> {
+        let region = self.shape().check_region(region)?;
+        let (data, context) = self.parts_mut();
+        let view = data.slice_mut(s![region.row_range(), region.col_range()]);
+        Ok(LensMut::new(view, region, context))
+    }
+
+    // Create a LensMut over the whole Matrix
+    pub fn as_lens_mut(&mut self) -> LensMut<'_, V> {
+        let region = self.shape().region();
+        let (data, context) = self.parts_mut();
+        LensMut::new(data.view_mut(), region, context)
+    }
+
+    // Create one LensMut per region; the regions must be pairwise disjoint
+    pub fn lenses_mut(&mut self, regions: &[Region]) -> Result<Vec<LensMut<'_, V>>, MatricalError> {
+        self.as_lens_mut().into_disjoint(regions)
     }
 }
 
@@ -87,7 +550,10 @@ where
         }
         // Otherwise, return an error
         else {
-            Err(MatricalError::InvalidValue)
+            Err(MatricalError::InvalidValue {
+                operation: "is_valid",
+                index: None,
+            })
         }
     }
 }
@@ -123,35 +589,10 @@ impl<T> MatrixValidationStrategy<T> for IsValidStrategy<T> {
         }
         // Otherwise, return an error
         else {
-            Err(MatricalError::InvalidValue)
-        }
-    }
-}
-
-pub struct MatrixLensStrategy<T> {
-    validator: Box<dyn Fn(&MatrixLens<T>) -> bool>,
-}
-
-impl<T> MatrixLensStrategy<T> {
-    pub fn new<F>(validator: F) -> Self
-    where
-        F: 'static + Fn(&MatrixLens<T>) -> bool,
-    {
-        Self {
-            validator: Box::new(validator),
-        }
-    }
-}
-
-impl<T> MatrixValidationStrategy<MatrixLens<T>> for MatrixLensStrategy<T> {
-    fn is_valid(&self, context: &MatrixLens<T>) -> Result<(), MatricalError> {
-        // If the context is valid, return Ok
-        if (self.validator)(context) {
-            Ok(())
-        }
-        // Otherwise, return an error
-        else {
-            Err(MatricalError::InvalidContext)
+            Err(MatricalError::InvalidValue {
+                operation: "is_valid",
+                index: None,
+            })
         }
     }
 }
@@ -266,39 +707,6 @@ impl<T> MatrixValidationBuilder<T> {
 
 ///////////////////////////////
 
-    // Sizing
-    // fn execute(&self, Lens: &mut Lens<V>) -> Result<(), MatricalError> {
-    //     // Check if the coordinates are within the Lens dimensions
-    //     if self.top_left.0 >= Lens.data.shape()[0]
-    //         || self.top_left.1 >= Lens.data.shape()[1]
-    //         || self.bottom_right.0 >= Lens.data.shape()[0]
-    //         || self.bottom_right.1 >= Lens.data.shape()[1]
-    //     {
-    //         return Err(MatricalError::Custom(String::from("Coordinates are out of bounds")));
-    //     }
-
-    //     // Create a mutable sub-Lens lens
-    //     // The lens is a mutable reference to the original Lens's data
-    //     // It represents a lens into the sub-Lens
-    //     let lens = Lens.data.slice_mut(s![self.top_left.0..=self.bottom_right.0, self.top_left.1..=self.bottom_right.1]);
-
-    //     // Iterate over the lens and modify the values
-    //     for i in self.top_left.0..=self.bottom_right.0 {
-    //         for j in self.top_left.1..=self.bottom_right.1 {
-    //             let value = &mut lens[[i - self.top_left.0, j - self.top_left.1]];
-    //             // Modify the value as desired
-    //             *value += 1.0;
-    //         }
-    //     }
-
-    //     Ok(())
-    // }
-
-
-
-
-
-
 /*
 // Add the Lens Strategy instances to the DependencyInjectionContainer
 fn add_lens_strategies(container: &mut DependencyInjectionContainer) {
@@ -315,3 +723,218 @@ fn initialize_di_container() -> DependencyInjectionContainer {
 }
 */
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::tag::{Tag, TagKey};
+
+    fn matrix() -> Matrix<i32> {
+        Matrix::from_fn((4, 5), |row, col| (row * 10 + col) as i32).unwrap()
+    }
+
+    fn region(top_left: (usize, usize), bottom_right: (usize, usize)) -> Region {
+        Region::inclusive(top_left.into(), bottom_right.into()).unwrap()
+    }
+
+    #[test]
+    fn test_lens_is_zero_copy() {
+        let matrix = matrix();
+        let lens = matrix.lens(region((1, 1), (2, 3))).unwrap();
+
+        assert_eq!(lens.rows(), 2);
+        assert_eq!(lens.cols(), 3);
+        assert_eq!(*lens.get((0, 0)).unwrap(), 11);
+        assert!(std::ptr::eq(lens.get((1, 2)).unwrap(), matrix.get((2, 3)).unwrap()));
+    }
+
+    #[test]
+    fn test_lens_bounds() {
+        let matrix = matrix();
+        assert!(matches!(
+            matrix.lens(region((2, 2), (4, 4))),
+            Err(MatricalError::RegionOutOfBounds { .. })
+        ));
+
+        let lens = matrix.lens(region((1, 1), (2, 3))).unwrap();
+        assert!(matches!(lens.get((2, 0)), Err(MatricalError::IndexOutOfBounds { .. })));
+        assert!(matches!(lens.row(2), Err(MatricalError::IndexOutOfBounds { .. })));
+        assert!(matches!(lens.col(3), Err(MatricalError::IndexOutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_nested_lens() {
+        let matrix = matrix();
+        let outer = matrix.lens(region((1, 1), (3, 4))).unwrap();
+        let inner = outer.lens(region((1, 1), (2, 2))).unwrap();
+
+        assert_eq!(inner.region(), region((2, 2), (3, 3)));
+        assert_eq!(inner.iter().copied().collect::<Vec<_>>(), vec![22, 23, 32, 33]);
+        assert!(outer.lens(region((0, 0), (3, 0))).is_err());
+    }
+
+    #[test]
+    fn test_row_and_column_iterators() {
+        let matrix = matrix();
+        let lens = matrix.lens(region((0, 1), (1, 2))).unwrap();
+
+        let rows: Vec<Vec<i32>> = lens.iter_rows().map(|row| row.iter().copied().collect()).collect();
+        assert_eq!(rows, vec![vec![1, 2], vec![11, 12]]);
+
+        let cols: Vec<Vec<i32>> = lens.iter_cols().map(|col| col.iter().copied().collect()).collect();
+        assert_eq!(cols, vec![vec![1, 11], vec![2, 12]]);
+        assert_eq!(lens.col(1).unwrap().region(), region((0, 2), (1, 2)));
+    }
+
+    #[test]
+    fn test_indexed_iter_and_view() {
+        let matrix = matrix();
+        let lens = matrix.lens(region((3, 3), (3, 4))).unwrap();
+
+        let indexed: Vec<((usize, usize), i32)> = lens.indexed_iter().map(|(i, v)| (i.into(), *v)).collect();
+        assert_eq!(indexed, vec![((0, 0), 33), ((0, 1), 34)]);
+        assert_eq!(lens.view().shape(), &[1, 2]);
+        assert_eq!(lens.to_matrix().as_array(), &lens.view());
+    }
+
+    #[test]
+    fn test_tags_follow_the_lens() {
+        let mut matrix = matrix();
+        matrix.tags_mut().insert(Tag::feature_schema("grid-v1").unwrap()).unwrap();
+        matrix.row_tags_mut(3).unwrap().insert(Tag::custom("session", "b").unwrap()).unwrap();
+        matrix.col_tags_mut(4).unwrap().insert(Tag::unit("ms").unwrap()).unwrap();
+
+        let lens = matrix.lens(region((2, 3), (3, 4))).unwrap();
+        assert_eq!(lens.tags(), matrix.tags());
+        assert!(lens.row_tags(0).unwrap().is_empty());
+        assert_eq!(lens.row_tags(1).unwrap().get(&TagKey::Custom("session".into())).unwrap().value(), "b");
+        assert_eq!(lens.col_tags(1).unwrap().get(&TagKey::Unit).unwrap().value(), "ms");
+        assert!(lens.row_tags(2).is_err());
+
+        let copied = lens.to_matrix();
+        assert_eq!(copied.tags(), matrix.tags());
+        assert_eq!(copied.row_tags(1).unwrap(), matrix.row_tags(3).unwrap());
+        assert_eq!(copied.col_tags(1).unwrap(), matrix.col_tags(4).unwrap());
+
+        let lens = matrix.as_lens_mut();
+        assert!(lens.col_tags(4).unwrap().contains(&Tag::unit("ms").unwrap()));
+    }
+
+    #[test]
+    fn test_empty_lens() {
+        let matrix = matrix();
+        let lens = matrix.lens(Region::new(Index::new(4, 0), Index::new(4, 5)).unwrap()).unwrap();
+        assert!(lens.is_empty());
+        assert_eq!(lens.iter().count(), 0);
+        assert_eq!(lens.iter_rows().count(), 0);
+    }
+
+    #[test]
+    fn test_lens_mut_writes_through() {
+        let mut matrix = matrix();
+        let mut lens = matrix.lens_mut(region((1, 1), (2, 2))).unwrap();
+        lens.set((0, 0), -1).unwrap();
+        *lens.get_mut((1, 1)).unwrap() = -2;
+        assert!(lens.set((2, 0), 0).is_err());
+
+        assert_eq!(*matrix.get((1, 1)).unwrap(), -1);
+        assert_eq!(*matrix.get((2, 2)).unwrap(), -2);
+    }
+
+    #[test]
+    fn test_fill_and_map_inplace() {
+        let mut matrix = matrix();
+        matrix.lens_mut(region((0, 0), (0, 4))).unwrap().fill(0);
+        matrix.lens_mut(region((1, 0), (1, 1))).unwrap().map_inplace(|value| *value *= 2);
+
+        assert_eq!(matrix.as_lens().row(0).unwrap().iter().sum::<i32>(), 0);
+        assert_eq!(matrix.as_lens().row(1).unwrap().iter().copied().collect::<Vec<_>>(), vec![20, 22, 12, 13, 14]);
+    }
+
+    #[test]
+    fn test_assign_from() {
+        let source = Matrix::from_fn((2, 2), |row, col| (100 + row * 2 + col) as i32).unwrap();
+        let mut matrix = matrix();
+
+        let mut lens = matrix.lens_mut(region((2, 3), (3, 4))).unwrap();
+        lens.assign_from(&source.as_lens()).unwrap();
+        assert!(matches!(
+            lens.assign_from(&source.as_lens().row(0).unwrap()),
+            Err(MatricalError::ShapeMismatch { .. })
+        ));
+
+        assert_eq!(*matrix.get((3, 4)).unwrap(), 103);
+    }
+
+    #[test]
+    fn test_split_at_row_and_col() {
+        let mut matrix = matrix();
+        let lens = matrix.lens_mut(region((1, 1), (3, 4))).unwrap();
+        let (mut top, bottom) = lens.split_at_row(1).unwrap();
+        assert_eq!(top.region(), region((1, 1), (1, 4)));
+        assert_eq!(bottom.region(), region((2, 1), (3, 4)));
+
+        let (mut left, mut right) = bottom.split_at_col(2).unwrap();
+        assert_eq!(left.region(), region((2, 1), (3, 2)));
+        assert_eq!(right.region(), region((2, 3), (3, 4)));
+
+        top.fill(1);
+        left.fill(2);
+        right.fill(3);
+        assert_eq!(*matrix.get((1, 4)).unwrap(), 1);
+        assert_eq!(*matrix.get((3, 2)).unwrap(), 2);
+        assert_eq!(*matrix.get((2, 3)).unwrap(), 3);
+        assert_eq!(*matrix.get((0, 0)).unwrap(), 0);
+
+        let lens = matrix.as_lens_mut();
+        assert!(lens.split_at_row(5).is_err());
+    }
+
+    #[test]
+    fn test_disjoint_lenses() {
+        let mut matrix = matrix();
+        let windows = [region((2, 3), (3, 4)), region((0, 0), (1, 1)), region((3, 0), (3, 2))];
+        let mut lenses = matrix.lenses_mut(&windows).unwrap();
+
+        assert_eq!(lenses.len(), 3);
+        for (lens, window) in lenses.iter().zip(windows.iter()) {
+            assert_eq!(lens.region(), *window);
+        }
+        lenses[0].fill(-1);
+        lenses[1].fill(-2);
+        lenses[2].fill(-3);
+
+        assert_eq!(*matrix.get((3, 4)).unwrap(), -1);
+        assert_eq!(*matrix.get((1, 1)).unwrap(), -2);
+        assert_eq!(*matrix.get((3, 2)).unwrap(), -3);
+        assert_eq!(*matrix.get((2, 2)).unwrap(), 22);
+    }
+
+    #[test]
+    fn test_disjoint_lenses_reject_overlap() {
+        let mut matrix = matrix();
+        let result = matrix.lenses_mut(&[region((0, 0), (1, 1)), region((1, 1), (2, 2))]);
+        assert!(matches!(result, Err(MatricalError::OverlappingRegions { .. })));
+
+        let result = matrix.lenses_mut(&[region((0, 0), (4, 1))]);
+        assert!(matches!(result, Err(MatricalError::RegionOutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_disjoint_lenses_pinwheel() {
+        let mut matrix: Matrix<i32> = Matrix::zeros((3, 3)).unwrap();
+        let pinwheel = [
+            region((0, 0), (0, 1)),
+            region((0, 2), (1, 2)),
+            region((2, 1), (2, 2)),
+            region((1, 0), (2, 0)),
+        ];
+        assert!(matches!(
+            matrix.lenses_mut(&pinwheel),
+            Err(MatricalError::Unsupported { .. })
+        ));
+
+        let with_centre = [pinwheel[0], Region::new(Index::new(1, 1), Index::new(1, 1)).unwrap()];
+        assert_eq!(matrix.lenses_mut(&with_centre).unwrap().len(), 2);
+    }
+}
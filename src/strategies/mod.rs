@@ -1,13 +1,29 @@
 
+pub mod baseline;
 pub mod cog;
+pub mod gather;
 pub mod gear;
 pub mod lens;
+pub mod pipeline;
+pub mod report;
+pub mod rolling;
+pub mod selector;
+pub mod statistics;
 pub mod tag;
+pub mod validity;
 
+pub use baseline::*;
 pub use cog::*;
+pub use gather::*;
 pub use gear::*;
 pub use lens::*;
+pub use pipeline::*;
+pub use report::*;
+pub use rolling::*;
+pub use selector::*;
+pub use statistics::*;
 pub use tag::*;
+pub use validity::*;
 
 
 
@@ -0,0 +1,481 @@
+use crate::error::MatricalError;
+use crate::schematics::matrix::Matrix;
+use crate::strategies::cog::{DynGearMut, Policies};
+use crate::strategies::gear::{Gear, GearMut};
+use crate::strategies::lens::{Lens, LensMut};
+use crate::strategies::report::{GearOutput, OperationId, StageReport};
+use crate::strategies::rolling::Rolled;
+use crate::strategies::tag::Tagged;
+
+use std::fmt;
+
+// Gear pipelines
+//
+// `first.then(second)` feeds the output of one Gear into the next, and for
+// GearMuts applies them one after the other to the same target. The output
+// of a read-only stage must be a `GearInput` whose values are what the next
+// Gear reads, so a mismatched pipeline does not compile. `fan_out` applies two
+// Gears to the same input and `when` applies a Gear only if a predicate on its
+// input holds. Each combinator's Policy pairs the Policies of its parts, and
+// applying it through a GearContext records one stage per Gear that ran.
+// Pipelines assembled at runtime use `DynPipeline` instead.
+//
+
+// An owned Gear output that a later Gear can read through a Lens
+pub trait GearInput: GearOutput {
+    type Value;
+
+    fn lens(&self) -> Lens<'_, Self::Value>;
+}
+
+impl<V> GearInput for Matrix<V> {
+    type Value = V;
+
+    fn lens(&self) -> Lens<'_, V> {
+        self.as_lens()
+    }
+}
+
+impl<V> GearInput for Rolled<V> {
+    type Value = V;
+
+    fn lens(&self) -> Lens<'_, V> {
+        self.values().as_lens()
+    }
+}
+
+impl<T: GearInput> GearInput for Tagged<T> {
+    type Value = T::Value;
+
+    fn lens(&self) -> Lens<'_, T::Value> {
+        self.value().lens()
+    }
+}
+
+// Name a combinator after the operations it combines
+pub(crate) fn combined(name: &str, parts: &[OperationId]) -> OperationId {
+    let parts: Vec<String> = parts.iter().map(OperationId::to_string).collect();
+    OperationId::new(format!("{}({})", name, parts.join(", ")), 1)
+}
+
+// The Then struct, applying `second` after `first`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Then<A, B> {
+    // Create a new Then; usually built with `Gear::then` or `GearMut::then`
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<V, A, B> Gear<V> for Then<A, B>
+where
+    A: Gear<V>,
+    A::Output: GearInput,
+    B: Gear<<A::Output as GearInput>::Value>,
+{
+    type Output = B::Output;
+    type Policy = (A::Policy, B::Policy);
+
+    fn apply(&self, input: &Lens<'_, V>, policy: &Self::Policy) -> Result<B::Output, MatricalError> {
+        let intermediate = self.first.apply(input, &policy.0)?;
+        self.second.apply(&intermediate.lens(), &policy.1)
+    }
+
+    fn operation(&self) -> OperationId {
+        combined("then", &[self.first.operation(), self.second.operation()])
+    }
+
+    fn apply_staged(
+        &self,
+        input: &Lens<'_, V>,
+        policy: &Self::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<B::Output, MatricalError>
+    where
+        B::Output: GearOutput,
+    {
+        let intermediate = self.first.apply_staged(input, &policy.0, stages)?;
+        self.second.apply_staged(&intermediate.lens(), &policy.1, stages)
+    }
+}
+
+impl<V, A, B> GearMut<V> for Then<A, B>
+where
+    A: GearMut<V>,
+    B: GearMut<V>,
+{
+    type Policy = (A::Policy, B::Policy);
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &Self::Policy) -> Result<(), MatricalError> {
+        self.first.apply_mut(target, &policy.0)?;
+        self.second.apply_mut(target, &policy.1)
+    }
+
+    fn operation(&self) -> OperationId {
+        combined("then", &[self.first.operation(), self.second.operation()])
+    }
+
+    fn apply_mut_staged(
+        &self,
+        target: &mut LensMut<'_, V>,
+        policy: &Self::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<(), MatricalError> {
+        self.first.apply_mut_staged(target, &policy.0, stages)?;
+        self.second.apply_mut_staged(target, &policy.1, stages)
+    }
+}
+
+// The FanOut struct, applying two Gears to the same input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FanOut<A, B> {
+    left: A,
+    right: B,
+}
+
+impl<A, B> FanOut<A, B> {
+    // Create a new FanOut; usually built with `Gear::fan_out`
+    pub fn new(left: A, right: B) -> Self {
+        Self { left, right }
+    }
+}
+
+impl<V, A: Gear<V>, B: Gear<V>> Gear<V> for FanOut<A, B>
+where
+    A::Output: GearOutput,
+    B::Output: GearOutput,
+{
+    type Output = (A::Output, B::Output);
+    type Policy = (A::Policy, B::Policy);
+
+    fn apply(&self, input: &Lens<'_, V>, policy: &Self::Policy) -> Result<Self::Output, MatricalError> {
+        Ok((self.left.apply(input, &policy.0)?, self.right.apply(input, &policy.1)?))
+    }
+
+    fn operation(&self) -> OperationId {
+        combined("fan_out", &[self.left.operation(), self.right.operation()])
+    }
+
+    fn apply_staged(
+        &self,
+        input: &Lens<'_, V>,
+        policy: &Self::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<Self::Output, MatricalError> {
+        let left = self.left.apply_staged(input, &policy.0, stages)?;
+        let right = self.right.apply_staged(input, &policy.1, stages)?;
+        Ok((left, right))
+    }
+}
+
+// The When struct, applying a Gear only if a predicate on its input holds
+#[derive(Clone, Copy)]
+pub struct When<P, G> {
+    predicate: P,
+    gear: G,
+}
+
+// Apply `gear` only if `predicate` holds for the Lens it would be applied to
+pub fn when<P, G>(predicate: P, gear: G) -> When<P, G> {
+    When { predicate, gear }
+}
+
+impl<P, G: fmt::Debug> fmt::Debug for When<P, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("When").field("gear", &self.gear).finish_non_exhaustive()
+    }
+}
+
+// A read-only When outputs `None` when the predicate does not hold
+impl<V, P, G> Gear<V> for When<P, G>
+where
+    P: Fn(&Lens<'_, V>) -> bool,
+    G: Gear<V>,
+    G::Output: GearOutput,
+{
+    type Output = Option<G::Output>;
+    type Policy = G::Policy;
+
+    fn apply(&self, input: &Lens<'_, V>, policy: &G::Policy) -> Result<Self::Output, MatricalError> {
+        if !(self.predicate)(input) {
+            return Ok(None);
+        }
+        self.gear.apply(input, policy).map(Some)
+    }
+
+    fn operation(&self) -> OperationId {
+        combined("when", &[self.gear.operation()])
+    }
+
+    fn apply_staged(
+        &self,
+        input: &Lens<'_, V>,
+        policy: &G::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<Self::Output, MatricalError> {
+        if !(self.predicate)(input) {
+            stages.push(StageReport::skipped(self.gear.operation(), policy, input.shape()));
+            return Ok(None);
+        }
+        self.gear.apply_staged(input, policy, stages).map(Some)
+    }
+}
+
+impl<V, P, G> GearMut<V> for When<P, G>
+where
+    P: Fn(&Lens<'_, V>) -> bool,
+    G: GearMut<V>,
+{
+    type Policy = G::Policy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &G::Policy) -> Result<(), MatricalError> {
+        if !(self.predicate)(&target.as_lens()) {
+            return Ok(());
+        }
+        self.gear.apply_mut(target, policy)
+    }
+
+    fn operation(&self) -> OperationId {
+        combined("when", &[self.gear.operation()])
+    }
+
+    fn apply_mut_staged(
+        &self,
+        target: &mut LensMut<'_, V>,
+        policy: &G::Policy,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<(), MatricalError> {
+        if !(self.predicate)(&target.as_lens()) {
+            stages.push(StageReport::skipped(self.gear.operation(), policy, target.shape()));
+            return Ok(());
+        }
+        self.gear.apply_mut_staged(target, policy, stages)
+    }
+}
+
+// The DynPipeline struct
+//
+// An in-place pipeline assembled at runtime, for example from configuration.
+// Each step is a boxed GearMut that finds its Cog in the shared Policies, so
+// a missing policy surfaces as a `MissingPolicy` error from the step that
+// needed it. Steps run in the order they were added and stop at the first
+// error.
+//
+pub struct DynPipeline<V> {
+    steps: Vec<Box<dyn DynGearMut<V>>>,
+}
+
+impl<V> DynPipeline<V> {
+    // Create a new, empty DynPipeline
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    // Add a step, builder style
+    pub fn step<G: DynGearMut<V> + 'static>(mut self, gear: G) -> Self {
+        self.steps.push(Box::new(gear));
+        self
+    }
+
+    // Add an already boxed step
+    pub fn push(&mut self, gear: Box<dyn DynGearMut<V>>) {
+        self.steps.push(gear);
+    }
+
+    // Get the number of steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    // Check whether the pipeline has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl<V> Default for DynPipeline<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> fmt::Debug for DynPipeline<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DynPipeline").field("steps", &self.steps.len()).finish()
+    }
+}
+
+impl<V> GearMut<V> for DynPipeline<V> {
+    type Policy = Policies;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &Policies) -> Result<(), MatricalError> {
+        self.steps.iter().try_for_each(|step| step.apply_dyn_mut(target, policy))
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("dyn_pipeline", 1)
+    }
+
+    fn apply_mut_staged(
+        &self,
+        target: &mut LensMut<'_, V>,
+        policy: &Policies,
+        stages: &mut Vec<StageReport>,
+    ) -> Result<(), MatricalError> {
+        self.steps
+            .iter()
+            .try_for_each(|step| step.apply_dyn_mut_staged(target, policy, stages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::shape::{Axis, Region, Shape};
+    use crate::strategies::cog::NoPolicy;
+    use crate::strategies::gear::{AddConstant, Collect, GearContext};
+    use crate::strategies::report::Outcome;
+    use crate::operations::aggregate::Reducer;
+    use crate::strategies::rolling::Rolling;
+    use crate::strategies::statistics::{Ddof, MeanCenter, ZScore};
+    use crate::error::ErrorKind;
+
+    // A downstream-style Gear reading a whole Lens
+    struct Total;
+
+    impl Gear<f64> for Total {
+        type Output = f64;
+        type Policy = NoPolicy;
+
+        fn apply(&self, input: &Lens<'_, f64>, _policy: &NoPolicy) -> Result<f64, MatricalError> {
+            Ok(input.iter().sum())
+        }
+
+        fn operation(&self) -> OperationId {
+            OperationId::new("total", 1)
+        }
+    }
+
+    fn matrix() -> Matrix<f64> {
+        Matrix::from_fn((4, 2), |row, col| (row * 2 + col) as f64).unwrap()
+    }
+
+    #[test]
+    fn test_then_feeds_output_forward() {
+        let matrix = matrix();
+        let pipeline = Rolling::new(2, Axis::Column, Reducer::Sum).then(Collect).then(Total);
+        let total = pipeline.apply(&matrix.as_lens(), &((NoPolicy, NoPolicy), NoPolicy)).unwrap();
+        // Column sums of consecutive row pairs: (0+2, 1+3), (2+4, 3+5), (4+6, 5+7)
+        assert_eq!(total, 2.0 + 4.0 + 6.0 + 8.0 + 10.0 + 12.0);
+    }
+
+    #[test]
+    fn test_then_mut_threads_policies() {
+        let mut matrix = matrix();
+        let pipeline = AddConstant::new(10.0).then(ZScore::new(Axis::Column));
+        let context = GearContext::new(Region::new((0, 0).into(), (4, 2).into()).unwrap());
+        let (result, report) = context.apply_mut(&pipeline, &(NoPolicy, Ddof(0)), &mut matrix);
+        result.unwrap();
+
+        let column: Vec<f64> = matrix.as_lens().col(0).unwrap().iter().copied().collect();
+        assert!((column.iter().sum::<f64>()).abs() < 1e-12);
+        assert_eq!(report.operation().name(), "then(add_constant@1, z_score@1)");
+        assert_eq!(report.policy(), "none+ddof=0");
+        let names: Vec<&str> = report.stages().iter().map(|stage| stage.operation().name()).collect();
+        assert_eq!(names, vec!["add_constant", "z_score"]);
+        assert_eq!(report.stages()[1].policy(), "ddof=0");
+    }
+
+    #[test]
+    fn test_fan_out() {
+        let matrix = matrix();
+        let context = GearContext::new(Region::new((0, 0).into(), (2, 2).into()).unwrap());
+        let (result, report) = context.apply(&Total.fan_out(Collect), &(NoPolicy, NoPolicy), &matrix);
+        let (total, copy) = result.unwrap();
+
+        assert_eq!(total, 6.0);
+        assert_eq!(copy.shape(), Shape::new(2, 2).unwrap());
+        assert_eq!(report.stages().len(), 2);
+        assert!(report.allocated());
+        assert!(!report.stages()[0].allocated());
+    }
+
+    #[test]
+    fn test_when_records_skipped_stages() {
+        let mut matrix = matrix();
+        let centred = when(|lens: &Lens<'_, f64>| lens.rows() > 4, MeanCenter::new(Axis::Column));
+        let pipeline = centred.then(AddConstant::new(1.0));
+        let context = GearContext::new(Region::new((0, 0).into(), (4, 2).into()).unwrap());
+        let (result, report) = context.apply_mut(&pipeline, &(NoPolicy, NoPolicy), &mut matrix);
+        result.unwrap();
+
+        assert_eq!(*matrix.get((0, 0)).unwrap(), 1.0);
+        assert_eq!(report.stages()[0].outcome(), &Outcome::Skipped);
+        assert!(report.stages()[1].outcome().is_success());
+
+        let read = when(|lens: &Lens<'_, f64>| lens.cols() == 2, Total);
+        assert_eq!(read.apply(&matrix.as_lens(), &NoPolicy).unwrap(), Some(36.0));
+    }
+
+    #[test]
+    fn test_when_keeps_the_stages_of_its_gear() {
+        let matrix = matrix();
+        let context = GearContext::new(Region::new((0, 0).into(), (4, 2).into()).unwrap());
+        let pipeline = when(|lens: &Lens<'_, f64>| lens.rows() == 4, Gear::<f64>::then(Collect, Total));
+        let (result, report) = context.apply(&pipeline, &(NoPolicy, NoPolicy), &matrix);
+        assert_eq!(result.unwrap(), Some(28.0));
+        assert_eq!(report.stages().len(), 2);
+        assert!(report.stages().iter().all(|stage| stage.outcome().is_success()));
+
+        let skipped = when(|lens: &Lens<'_, f64>| lens.rows() > 4, Gear::<f64>::then(Collect, Total));
+        let (result, report) = context.apply(&skipped, &(NoPolicy, NoPolicy), &matrix);
+        assert_eq!(result.unwrap(), None);
+        assert_eq!(report.stages().len(), 1);
+        assert_eq!(report.stages()[0].outcome(), &Outcome::Skipped);
+    }
+
+    #[test]
+    fn test_failed_stage_stops_the_pipeline() {
+        let mut matrix = Matrix::from_shape_vec((1, 2), vec![1.0, 2.0]).unwrap();
+        let pipeline = Then::new(ZScore::new(Axis::Column), AddConstant::new(1.0));
+        let context = GearContext::new(Region::new((0, 0).into(), (1, 2).into()).unwrap());
+        let (result, report) = context.apply_mut(&pipeline, &(Ddof(1), NoPolicy), &mut matrix);
+
+        assert!(result.is_err());
+        assert_eq!(report.stages().len(), 1);
+        assert!(matches!(
+            report.outcome(),
+            Outcome::Failure { kind: ErrorKind::InsufficientData, .. }
+        ));
+        assert_eq!(matrix.iter().copied().collect::<Vec<_>>(), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_dyn_pipeline() {
+        let mut matrix = Matrix::from_shape_vec((2, 1), vec![1.0, 3.0]).unwrap();
+        let mut pipeline = DynPipeline::new().step(AddConstant::new(1.0));
+        pipeline.push(Box::new(ZScore::new(Axis::Column)));
+        assert_eq!(pipeline.len(), 2);
+
+        let context = GearContext::new(Region::new((0, 0).into(), (2, 1).into()).unwrap());
+        let (result, report) = context.apply_mut(&pipeline, &Policies::new(), &mut matrix);
+        assert!(matches!(result, Err(MatricalError::MissingPolicy { .. })));
+        assert_eq!(report.stages().len(), 2);
+        assert!(matches!(
+            report.stages()[1].outcome(),
+            Outcome::Failure { kind: ErrorKind::MissingPolicy, .. }
+        ));
+
+        let policies = Policies::new().with(Ddof(0));
+        let (result, report) = context.apply_mut(&pipeline, &policies, &mut matrix);
+        result.unwrap();
+        assert_eq!(matrix.iter().copied().collect::<Vec<_>>(), vec![-1.0, 1.0]);
+        assert_eq!(report.policy(), "ddof=0+none");
+        assert_eq!(report.stages()[1].policy(), "ddof=0");
+    }
+}
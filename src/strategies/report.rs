@@ -0,0 +1,465 @@
+use crate::error::{ErrorKind, MatricalError};
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Region, Shape};
+use crate::strategies::cog::Cog;
+use crate::strategies::tag::{TagKey, TagSet, Tagged};
+
+use serde::{Deserialize, Serialize};
+
+use std::error::Error;
+use std::fmt;
+
+// The identity of a Gear: a stable name and the version of its behaviour
+//
+// The version is bumped whenever a Gear changes what it computes, so replayed
+// reports show which behaviour produced a result.
+//
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OperationId {
+    name: String,
+    version: u32,
+}
+
+impl OperationId {
+    // Create a new OperationId
+    pub fn new(name: impl Into<String>, version: u32) -> Self {
+        Self {
+            name: name.into(),
+            version,
+        }
+    }
+
+    // Get the name of the operation
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Get the version of the operation
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+}
+
+impl fmt::Display for OperationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+// Describes a Gear output for an ExecutionReport
+pub trait GearOutput {
+    // The shape of the output, if it is matrix-like
+    fn output_shape(&self) -> Option<Shape> {
+        None
+    }
+
+    // Whether producing the output allocated new Matrix storage
+    fn allocated(&self) -> bool {
+        false
+    }
+}
+
+impl<V> GearOutput for Matrix<V> {
+    fn output_shape(&self) -> Option<Shape> {
+        Some(self.shape())
+    }
+
+    fn allocated(&self) -> bool {
+        true
+    }
+}
+
+impl<T: GearOutput> GearOutput for Tagged<T> {
+    fn output_shape(&self) -> Option<Shape> {
+        self.value().output_shape()
+    }
+
+    fn allocated(&self) -> bool {
+        self.value().allocated()
+    }
+}
+
+impl<T> GearOutput for Vec<T> {
+    fn allocated(&self) -> bool {
+        true
+    }
+}
+
+impl<T: GearOutput> GearOutput for Option<T> {
+    fn output_shape(&self) -> Option<Shape> {
+        self.as_ref().and_then(GearOutput::output_shape)
+    }
+
+    fn allocated(&self) -> bool {
+        self.as_ref().is_some_and(GearOutput::allocated)
+    }
+}
+
+impl<A: GearOutput, B: GearOutput> GearOutput for (A, B) {
+    fn allocated(&self) -> bool {
+        self.0.allocated() || self.1.allocated()
+    }
+}
+
+impl GearOutput for () {}
+impl GearOutput for bool {}
+impl GearOutput for usize {}
+impl GearOutput for isize {}
+impl GearOutput for i32 {}
+impl GearOutput for i64 {}
+impl GearOutput for u32 {}
+impl GearOutput for u64 {}
+impl GearOutput for f32 {}
+impl GearOutput for f64 {}
+
+// Whether an application succeeded, and the kind of error if it did not
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Success,
+    // A conditional stage whose predicate did not hold
+    Skipped,
+    Failure {
+        // The kind of the innermost error
+        kind: ErrorKind,
+        // The error and its sources, outermost first
+        message: String,
+    },
+}
+
+impl Outcome {
+    // Record the result of an application
+    pub fn of<T>(result: &Result<T, MatricalError>) -> Self {
+        match result {
+            Ok(_) => Outcome::Success,
+            Err(error) => Outcome::failure(error),
+        }
+    }
+
+    // Record a failed application
+    pub fn failure(error: &MatricalError) -> Self {
+        let mut message = error.to_string();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        Outcome::Failure {
+            kind: error.root().kind(),
+            message,
+        }
+    }
+
+    // Check whether the application succeeded
+    pub fn is_success(&self) -> bool {
+        matches!(self, Outcome::Success)
+    }
+}
+
+// The StageReport struct
+//
+// A record of one Gear run inside an application. A single Gear produces one
+// stage; pipelines produce one per Gear they run, in the order they ran.
+//
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageReport {
+    // The operation that was run
+    operation: OperationId,
+    // The id of the Cog it ran under
+    policy: String,
+    // The shape of the Lens it saw
+    input_shape: Shape,
+    // The shape of its output, if it is matrix-like and the stage succeeded
+    output_shape: Option<Shape>,
+    // Whether it allocated new Matrix storage
+    allocated: bool,
+    // Whether it succeeded
+    outcome: Outcome,
+}
+
+impl StageReport {
+    // Record a read-only Gear stage
+    pub fn gear<T: GearOutput>(
+        operation: OperationId,
+        policy: &dyn Cog,
+        input_shape: Shape,
+        result: &Result<T, MatricalError>,
+    ) -> Self {
+        let (output_shape, allocated) = match result {
+            Ok(output) => (output.output_shape(), output.allocated()),
+            Err(_) => (None, false),
+        };
+        Self {
+            operation,
+            policy: policy.id(),
+            input_shape,
+            output_shape,
+            allocated,
+            outcome: Outcome::of(result),
+        }
+    }
+
+    // Record an in-place GearMut stage
+    pub fn gear_mut(
+        operation: OperationId,
+        policy: &dyn Cog,
+        shape: Shape,
+        result: &Result<(), MatricalError>,
+    ) -> Self {
+        Self {
+            operation,
+            policy: policy.id(),
+            input_shape: shape,
+            output_shape: result.as_ref().ok().map(|_| shape),
+            allocated: false,
+            outcome: Outcome::of(result),
+        }
+    }
+
+    // Record a stage that failed before it could run, such as a missing policy
+    pub fn failed(operation: OperationId, policy: impl Into<String>, input_shape: Shape, error: &MatricalError) -> Self {
+        Self {
+            operation,
+            policy: policy.into(),
+            input_shape,
+            output_shape: None,
+            allocated: false,
+            outcome: Outcome::failure(error),
+        }
+    }
+
+    // Record a conditional stage that did not run
+    pub fn skipped(operation: OperationId, policy: &dyn Cog, input_shape: Shape) -> Self {
+        Self {
+            operation,
+            policy: policy.id(),
+            input_shape,
+            output_shape: None,
+            allocated: false,
+            outcome: Outcome::Skipped,
+        }
+    }
+
+    // Get the operation that was run
+    pub fn operation(&self) -> &OperationId {
+        &self.operation
+    }
+
+    // Get the id of the Cog the stage ran under
+    pub fn policy(&self) -> &str {
+        &self.policy
+    }
+
+    // Get the shape of the Lens the stage saw
+    pub fn input_shape(&self) -> Shape {
+        self.input_shape
+    }
+
+    // Get the shape of the stage output
+    pub fn output_shape(&self) -> Option<Shape> {
+        self.output_shape
+    }
+
+    // Check whether the stage allocated new Matrix storage
+    pub fn allocated(&self) -> bool {
+        self.allocated
+    }
+
+    // Get how the stage ended
+    pub fn outcome(&self) -> &Outcome {
+        &self.outcome
+    }
+}
+
+// The ExecutionReport struct
+//
+// A record of one Gear or GearMut application: which operation ran, on which
+// Region, under which feature schema and Cog, the shapes going in and out,
+// whether new storage was allocated, and how it ended. Reports hold no
+// wall-clock time or other run-instance data, so replaying the same operation
+// on the same input produces an identical, byte-for-byte serialized report.
+// Pipelines accumulate one StageReport per Gear they ran into `stages`.
+//
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    // The operation that was applied
+    operation: OperationId,
+    // The Region of the Matrix the operation was applied to
+    region: Region,
+    // The feature schema Tagged on the Matrix, if any
+    schema: Option<String>,
+    // The id of the Cog the operation ran under
+    policy: String,
+    // The shape of the Lens the operation saw
+    input_shape: Shape,
+    // The shape of the output, if it is matrix-like and the operation succeeded
+    output_shape: Option<Shape>,
+    // Whether any stage allocated new Matrix storage
+    allocated: bool,
+    // Whether the operation succeeded
+    outcome: Outcome,
+    // The Gears that ran, in order
+    stages: Vec<StageReport>,
+}
+
+impl ExecutionReport {
+    // Create a new ExecutionReport for an application to `region` of a Matrix tagged with `tags`
+    pub(crate) fn new<C: Cog>(
+        operation: OperationId,
+        region: Region,
+        tags: &TagSet,
+        policy: &C,
+        output_shape: Option<Shape>,
+        outcome: Outcome,
+        stages: Vec<StageReport>,
+    ) -> Self {
+        Self {
+            operation,
+            region,
+            schema: tags.get(&TagKey::FeatureSchema).map(|tag| tag.value().to_owned()),
+            policy: policy.id(),
+            input_shape: region.shape(),
+            output_shape,
+            allocated: stages.iter().any(StageReport::allocated),
+            outcome,
+            stages,
+        }
+    }
+
+    // Get the operation that was applied
+    pub fn operation(&self) -> &OperationId {
+        &self.operation
+    }
+
+    // Get the Region the operation was applied to
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    // Get the feature schema the Matrix was Tagged with
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    // Get the id of the Cog the operation ran under
+    pub fn policy(&self) -> &str {
+        &self.policy
+    }
+
+    // Get the shape of the Lens the operation saw
+    pub fn input_shape(&self) -> Shape {
+        self.input_shape
+    }
+
+    // Get the shape of the output
+    pub fn output_shape(&self) -> Option<Shape> {
+        self.output_shape
+    }
+
+    // Check whether any stage allocated new Matrix storage
+    pub fn allocated(&self) -> bool {
+        self.allocated
+    }
+
+    // Get how the operation ended
+    pub fn outcome(&self) -> &Outcome {
+        &self.outcome
+    }
+
+    // Get the Gears that ran, in order
+    pub fn stages(&self) -> &[StageReport] {
+        &self.stages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::shape::{Axis, Index};
+    use crate::strategies::cog::NoPolicy;
+    use crate::strategies::gear::{AddConstant, Collect, GearContext};
+    use crate::operations::aggregate::Reducer;
+    use crate::strategies::rolling::Rolling;
+    use crate::strategies::statistics::{Ddof, ZScore};
+    use crate::strategies::tag::Tag;
+
+    fn matrix() -> Matrix<f64> {
+        let mut matrix = Matrix::from_fn((4, 3), |row, col| (row * 3 + col) as f64).unwrap();
+        matrix.tags_mut().insert(Tag::feature_schema("grid-v1").unwrap()).unwrap();
+        matrix
+    }
+
+    fn region(start: (usize, usize), end: (usize, usize)) -> Region {
+        Region::new(start.into(), end.into()).unwrap()
+    }
+
+    #[test]
+    fn test_report_for_gear() {
+        let matrix = matrix();
+        let context = GearContext::new(region((1, 0), (3, 2)));
+        let (result, report) = context.apply(&Collect, &NoPolicy, &matrix);
+
+        assert_eq!(result.unwrap().shape(), Shape::new(2, 2).unwrap());
+        assert_eq!(report.operation(), &OperationId::new("collect", 1));
+        assert_eq!(report.region(), region((1, 0), (3, 2)));
+        assert_eq!(report.schema(), Some("grid-v1"));
+        assert_eq!(report.policy(), "none");
+        assert_eq!(report.input_shape(), Shape::new(2, 2).unwrap());
+        assert_eq!(report.output_shape(), Some(Shape::new(2, 2).unwrap()));
+        assert!(report.allocated());
+        assert!(report.outcome().is_success());
+
+        let (_, rolled) = context.apply(&Rolling::new(2, Axis::Column, Reducer::Sum), &NoPolicy, &matrix);
+        assert_eq!(rolled.output_shape(), Some(Shape::new(1, 2).unwrap()));
+    }
+
+    #[test]
+    fn test_report_for_gear_mut() {
+        let mut matrix = matrix();
+        let context = GearContext::new(region((0, 1), (4, 3)));
+        let (result, report) = context.apply_mut(&ZScore::new(Axis::Column), &Ddof(1), &mut matrix);
+
+        assert!(result.is_ok());
+        assert_eq!(report.operation().name(), "z_score");
+        assert_eq!(report.policy(), "ddof=1");
+        assert_eq!(report.output_shape(), Some(report.input_shape()));
+        assert!(!report.allocated());
+    }
+
+    #[test]
+    fn test_report_records_typed_failure() {
+        let mut matrix = matrix();
+        let context = GearContext::new(region((2, 0), (5, 3)));
+        let (result, report) = context.apply_mut(&AddConstant::new(1.0), &NoPolicy, &mut matrix);
+
+        assert!(result.is_err());
+        assert_eq!(report.output_shape(), None);
+        assert!(matches!(
+            report.outcome(),
+            Outcome::Failure { kind: ErrorKind::RegionOutOfBounds, .. }
+        ));
+
+        let single = GearContext::new(region((0, 0), (1, 3)));
+        let (_, report) = single.apply_mut(&ZScore::new(Axis::Column), &Ddof(1), &mut matrix);
+        assert!(matches!(
+            report.outcome(),
+            Outcome::Failure { kind: ErrorKind::InsufficientData, .. }
+        ));
+    }
+
+    #[test]
+    fn test_report_replays_identically() {
+        let run = || {
+            let mut matrix = matrix();
+            let context = GearContext::new(Region::inclusive(Index::new(0, 0), Index::new(2, 2)).unwrap());
+            let (_, report) = context.apply_mut(&AddConstant::new(1.0), &NoPolicy, &mut matrix);
+            serde_json::to_string(&report).unwrap()
+        };
+        let first = run();
+        assert_eq!(first, run());
+
+        let restored: ExecutionReport = serde_json::from_str(&first).unwrap();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), first);
+        assert_eq!(restored.operation(), &OperationId::new("add_constant", 1));
+    }
+}
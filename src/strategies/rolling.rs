@@ -0,0 +1,268 @@
+use crate::error::MatricalError;
+use crate::operations::aggregate::Reducer;
+use crate::schematics::mask::Mask;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Axis, Shape};
+use crate::strategies::cog::NoPolicy;
+use crate::strategies::gear::Gear;
+use crate::strategies::lens::Lens;
+use crate::strategies::report::{GearOutput, OperationId};
+
+use ndarray::{s, Array2};
+use num_traits::Float;
+
+// How a Rolling Gear handles positions with fewer than `window` preceding values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    // Leave incomplete positions out of the output
+    Drop,
+    // Keep incomplete positions as NaN and mark them invalid
+    Pad,
+    // Reduce over the values that are available
+    Shrink,
+}
+
+// The Rolling struct
+//
+// A read-only Gear computing a statistic over trailing windows that slide
+// along each lane of a Lens: down each column for `Axis::Column`, across
+// each row for `Axis::Row`. A window ends at every `step`-th position of the
+// lane, starting from the first, and covers the `window` values up to and
+// including that position. Windows never reach outside the Lens, so applying
+// Rolling through a GearContext keeps it inside the Region.
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rolling<V> {
+    window: usize,
+    step: usize,
+    axis: Axis,
+    reducer: Reducer<V>,
+    edge: Edge,
+}
+
+impl<V> Rolling<V> {
+    // Create a new Rolling with a step of one that drops incomplete windows
+    pub fn new(window: usize, axis: Axis, reducer: Reducer<V>) -> Self {
+        Self {
+            window,
+            step: 1,
+            axis,
+            reducer,
+            edge: Edge::Drop,
+        }
+    }
+
+    // Set the distance between the ends of consecutive windows
+    pub fn step(mut self, step: usize) -> Self {
+        self.step = step;
+        self
+    }
+
+    // Set the policy for windows at the start of a lane
+    pub fn edge(mut self, edge: Edge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    // Get the Lens-relative lane positions each output window ends at
+    fn ends(&self, len: usize) -> Vec<usize> {
+        (0..len)
+            .step_by(self.step)
+            .filter(|end| self.edge != Edge::Drop || end + 1 >= self.window)
+            .collect()
+    }
+}
+
+impl<V: Float> Rolling<V> {
+    fn validate(&self) -> Result<(), MatricalError> {
+        if self.window == 0 || self.step == 0 {
+            return Err(MatricalError::InvalidValue {
+                operation: "rolling",
+                index: None,
+            });
+        }
+        self.reducer.check("rolling")
+    }
+}
+
+impl<V: Float> Gear<V> for Rolling<V> {
+    type Output = Rolled<V>;
+    type Policy = NoPolicy;
+
+    fn apply(&self, input: &Lens<'_, V>, _policy: &NoPolicy) -> Result<Rolled<V>, MatricalError> {
+        self.validate()?;
+        let view = input.view();
+        let (lanes, len) = match self.axis {
+            Axis::Column => (input.cols(), input.rows()),
+            Axis::Row => (input.rows(), input.cols()),
+        };
+        let ends = self.ends(len);
+        let shape = match self.axis {
+            Axis::Column => Shape::new(ends.len(), lanes)?,
+            Axis::Row => Shape::new(lanes, ends.len())?,
+        };
+
+        let mut values = Array2::from_elem((shape.rows(), shape.cols()), V::nan());
+        let mut valid = Mask::falses(shape);
+        let mut window = Vec::with_capacity(self.window);
+        for (lane_index, lane) in view.lanes(self.axis.lane_axis()).into_iter().enumerate() {
+            for (position, &end) in ends.iter().enumerate() {
+                let start = (end + 1).saturating_sub(self.window);
+                let complete = end + 1 >= self.window;
+                if !complete && self.edge == Edge::Pad {
+                    continue;
+                }
+                window.clear();
+                window.extend(lane.slice(s![start..=end]).iter().copied());
+                if let Some(value) = self.reducer.reduce(&window) {
+                    let cell = match self.axis {
+                        Axis::Column => (position, lane_index),
+                        Axis::Row => (lane_index, position),
+                    };
+                    values[cell] = value;
+                    valid.set(cell, true)?;
+                }
+            }
+        }
+
+        Ok(Rolled {
+            values: Matrix::from_array(values),
+            valid,
+            ends,
+        })
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("rolling", 1)
+    }
+}
+
+// The output of a Rolling Gear
+//
+// `values` holds one reduced value per window and lane; `valid` marks which
+// of them were computed from a window the reducer could use, so padded and
+// too-short windows are never mistaken for real NaN results.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rolled<V> {
+    values: Matrix<V>,
+    valid: Mask,
+    ends: Vec<usize>,
+}
+
+impl<V> Rolled<V> {
+    // Get the reduced values
+    pub fn values(&self) -> &Matrix<V> {
+        &self.values
+    }
+
+    // Get the validity of each reduced value
+    pub fn valid(&self) -> &Mask {
+        &self.valid
+    }
+
+    // Get the Lens-relative lane position each window ends at
+    pub fn ends(&self) -> &[usize] {
+        &self.ends
+    }
+
+    // Consume the output and return the values and their validity
+    pub fn into_parts(self) -> (Matrix<V>, Mask) {
+        (self.values, self.valid)
+    }
+}
+
+impl<V> GearOutput for Rolled<V> {
+    fn output_shape(&self) -> Option<Shape> {
+        Some(self.values.shape())
+    }
+
+    fn allocated(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::shape::Region;
+    use crate::strategies::gear::GearContext;
+
+    fn column() -> Matrix<f64> {
+        Matrix::from_shape_vec((5, 1), vec![1.0, 2.0, 3.0, 4.0, 10.0]).unwrap()
+    }
+
+    fn values(rolled: &Rolled<f64>) -> Vec<f64> {
+        rolled.values().iter().copied().collect()
+    }
+
+    #[test]
+    fn test_rolling_mean_drops_edges() {
+        let matrix = column();
+        let rolled = Rolling::new(3, Axis::Column, Reducer::Mean).apply(&matrix.as_lens(), &NoPolicy).unwrap();
+        assert_eq!(values(&rolled), vec![2.0, 3.0, 17.0 / 3.0]);
+        assert_eq!(rolled.ends(), &[2, 3, 4]);
+        assert_eq!(rolled.valid().count_ones(), 3);
+    }
+
+    #[test]
+    fn test_rolling_pad_marks_invalid() {
+        let matrix = column();
+        let rolled = Rolling::new(2, Axis::Column, Reducer::Sum)
+            .edge(Edge::Pad)
+            .step(2)
+            .apply(&matrix.as_lens(), &NoPolicy)
+            .unwrap();
+        let values = values(&rolled);
+        assert!(values[0].is_nan());
+        assert_eq!(&values[1..], &[5.0, 14.0]);
+        assert_eq!(rolled.valid().iter().collect::<Vec<_>>(), vec![false, true, true]);
+    }
+
+    #[test]
+    fn test_rolling_shrink() {
+        let matrix = column();
+        let rolled = Rolling::new(3, Axis::Column, Reducer::Max).edge(Edge::Shrink).apply(&matrix.as_lens(), &NoPolicy).unwrap();
+        assert_eq!(values(&rolled), vec![1.0, 2.0, 3.0, 4.0, 10.0]);
+
+        let variance = Rolling::new(3, Axis::Column, Reducer::Variance { ddof: 1 })
+            .edge(Edge::Shrink)
+            .apply(&matrix.as_lens(), &NoPolicy)
+            .unwrap();
+        assert_eq!(variance.valid().iter().collect::<Vec<_>>(), vec![false, true, true, true, true]);
+        assert_eq!(variance.values().get((1, 0)).copied().unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_rolling_rows() {
+        let matrix = Matrix::from_shape_vec((2, 4), vec![4.0, 1.0, 3.0, 2.0, 0.0, 0.0, 8.0, 8.0]).unwrap();
+        let rolled = Rolling::new(3, Axis::Row, Reducer::Median).apply(&matrix.as_lens(), &NoPolicy).unwrap();
+        assert_eq!(rolled.values().shape(), Shape::new(2, 2).unwrap());
+        assert_eq!(values(&rolled), vec![3.0, 2.0, 0.0, 8.0]);
+
+        let min = Rolling::new(2, Axis::Row, Reducer::Min).apply(&matrix.as_lens(), &NoPolicy).unwrap();
+        assert_eq!(values(&min), vec![1.0, 1.0, 2.0, 0.0, 0.0, 8.0]);
+        let quantile = Rolling::new(4, Axis::Row, Reducer::Quantile(0.25)).apply(&matrix.as_lens(), &NoPolicy).unwrap();
+        assert_eq!(values(&quantile), vec![1.75, 0.0]);
+    }
+
+    #[test]
+    fn test_rolling_respects_region() {
+        let matrix = column();
+        let context = GearContext::new(Region::new((1, 0).into(), (4, 1).into()).unwrap());
+        let rolled = context.apply(&Rolling::new(2, Axis::Column, Reducer::Sum), &NoPolicy, &matrix).0.unwrap();
+        assert_eq!(values(&rolled), vec![5.0, 7.0]);
+    }
+
+    #[test]
+    fn test_rolling_rejects_bad_parameters() {
+        let matrix = column();
+        let lens = matrix.as_lens();
+        assert!(Rolling::new(0, Axis::Column, Reducer::Mean).apply(&lens, &NoPolicy).is_err());
+        assert!(Rolling::new(2, Axis::Column, Reducer::Mean).step(0).apply(&lens, &NoPolicy).is_err());
+        assert!(Rolling::new(2, Axis::Column, Reducer::Quantile(1.5)).apply(&lens, &NoPolicy).is_err());
+
+        let empty = Rolling::new(9, Axis::Column, Reducer::Mean).apply(&lens, &NoPolicy).unwrap();
+        assert!(empty.values().is_empty());
+    }
+}
@@ -0,0 +1,552 @@
+use crate::error::MatricalError;
+use crate::schematics::shape::{Index, Shape};
+use crate::strategies::lens::{Lens, LensMut};
+
+use ndarray::{s, Axis, IndexLonger};
+
+use std::iter;
+use std::ops::Range;
+
+// Defines which cells of a rectangular Lens belong to a non-rectangular
+// selection. Indices are relative to the Lens being selected from, and
+// `shape` is that Lens's shape. Selectors that can describe each row as a
+// stepped range of columns do so in `row_span`, so a selection visits only
+// the cells it contains; the others are scanned cell by cell with `contains`.
+//
+pub trait Selector {
+    fn contains(&self, shape: Shape, index: Index) -> bool;
+
+    // Get the columns of `row` the selector contains, if they form a RowSpan
+    fn row_span(&self, _shape: Shape, _row: usize) -> Option<RowSpan> {
+        None
+    }
+
+    // Reject selectors whose parameters cannot describe a selection
+    fn validate(&self) -> Result<(), MatricalError> {
+        Ok(())
+    }
+}
+
+// The columns a Selector contains in one row: every `step`-th column of `cols`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowSpan {
+    pub cols: Range<usize>,
+    pub step: usize,
+}
+
+impl RowSpan {
+    // Create a new RowSpan of consecutive columns
+    pub fn new(cols: Range<usize>) -> Self {
+        Self { cols, step: 1 }
+    }
+
+    // Create a new RowSpan holding no columns
+    pub fn empty() -> Self {
+        Self::new(0..0)
+    }
+}
+
+// Cells on the k-th diagonal: `col - row == k`. Zero is the main diagonal,
+// positive values lie above it and negative values below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagonal(pub isize);
+
+impl Selector for Diagonal {
+    fn contains(&self, _shape: Shape, index: Index) -> bool {
+        index.col() as isize - index.row() as isize == self.0
+    }
+
+    fn row_span(&self, _shape: Shape, row: usize) -> Option<RowSpan> {
+        let col = row as isize + self.0;
+        Some(if col < 0 { RowSpan::empty() } else { RowSpan::new(col as usize..col as usize + 1) })
+    }
+}
+
+// Cells within `lower` diagonals below and `upper` diagonals above the main
+// diagonal, inclusive; `Band { lower: 0, upper: 0 }` is the main diagonal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Band {
+    pub lower: usize,
+    pub upper: usize,
+}
+
+impl Selector for Band {
+    fn contains(&self, _shape: Shape, index: Index) -> bool {
+        if index.col() >= index.row() {
+            index.col() - index.row() <= self.upper
+        } else {
+            index.row() - index.col() <= self.lower
+        }
+    }
+
+    fn row_span(&self, _shape: Shape, row: usize) -> Option<RowSpan> {
+        Some(RowSpan::new(row.saturating_sub(self.lower)..row.saturating_add(self.upper).saturating_add(1)))
+    }
+}
+
+// Cells on or above the main diagonal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpperTriangle;
+
+impl Selector for UpperTriangle {
+    fn contains(&self, _shape: Shape, index: Index) -> bool {
+        index.col() >= index.row()
+    }
+
+    fn row_span(&self, shape: Shape, row: usize) -> Option<RowSpan> {
+        Some(RowSpan::new(row..shape.cols()))
+    }
+}
+
+// Cells on or below the main diagonal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowerTriangle;
+
+impl Selector for LowerTriangle {
+    fn contains(&self, _shape: Shape, index: Index) -> bool {
+        index.col() <= index.row()
+    }
+
+    fn row_span(&self, _shape: Shape, row: usize) -> Option<RowSpan> {
+        Some(RowSpan::new(0..row + 1))
+    }
+}
+
+// Every `row_step`-th row and every `col_step`-th column, starting at (0, 0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strided {
+    pub row_step: usize,
+    pub col_step: usize,
+}
+
+impl Selector for Strided {
+    fn contains(&self, _shape: Shape, index: Index) -> bool {
+        index.row().is_multiple_of(self.row_step) && index.col().is_multiple_of(self.col_step)
+    }
+
+    fn row_span(&self, shape: Shape, row: usize) -> Option<RowSpan> {
+        if !row.is_multiple_of(self.row_step) {
+            return Some(RowSpan::empty());
+        }
+        Some(RowSpan {
+            cols: 0..shape.cols(),
+            step: self.col_step,
+        })
+    }
+
+    fn validate(&self) -> Result<(), MatricalError> {
+        if self.row_step == 0 || self.col_step == 0 {
+            return Err(MatricalError::InvalidValue {
+                operation: "strided",
+                index: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+// A borrowed, non-rectangular selection within a Lens
+//
+// The Selection keeps the rectangular Lens it was made from and only exposes
+// the cells its Selector contains. Nothing is copied; indices are relative to
+// the bounding Lens and unselected cells are reported as out of bounds.
+//
+#[derive(Debug, Clone)]
+pub struct Selection<'a, V, S> {
+    lens: Lens<'a, V>,
+    selector: S,
+}
+
+impl<'a, V, S: Selector> Selection<'a, V, S> {
+    // Get the bounding Lens
+    pub fn lens(&self) -> &Lens<'a, V> {
+        &self.lens
+    }
+
+    // Get the shape of the bounding Lens
+    pub fn shape(&self) -> Shape {
+        self.lens.shape()
+    }
+
+    // Check whether a Lens-relative index is selected
+    pub fn contains<I: Into<Index>>(&self, index: I) -> bool {
+        let index = index.into();
+        self.shape().contains(index) && self.selector.contains(self.shape(), index)
+    }
+
+    // Get the number of selected cells
+    pub fn len(&self) -> usize {
+        selected_len(self.shape(), &self.selector)
+    }
+
+    // Check whether no cell is selected
+    pub fn is_empty(&self) -> bool {
+        self.indices().next().is_none()
+    }
+
+    // Get a reference to a selected value
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<&'a V, MatricalError> {
+        let index = index.into();
+        if !self.contains(index) {
+            return Err(MatricalError::IndexOutOfBounds {
+                index,
+                shape: self.shape(),
+            });
+        }
+        self.lens.get(index)
+    }
+
+    // Iterate over the selected indices in row-major order
+    pub fn indices(&self) -> impl Iterator<Item = Index> + '_ {
+        selected(self.shape(), &self.selector)
+    }
+
+    // Iterate over the selected values in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &'a V> + '_ {
+        self.indexed_iter().map(|(_, value)| value)
+    }
+
+    // Iterate over the selected values with their Lens-relative index
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Index, &'a V)> + '_ {
+        let view = self.lens.view();
+        self.indices()
+            .map(move |index| (index, IndexLonger::index(&view, (index.row(), index.col()))))
+    }
+
+    // Copy the selected values into a Vec in row-major order
+    pub fn to_vec(&self) -> Vec<V>
+    where
+        V: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+}
+
+// A mutable, non-rectangular selection within a LensMut
+//
+// Writes through a SelectionMut only ever reach selected cells; the rest of
+// the bounding LensMut stays borrowed but untouched.
+//
+#[derive(Debug)]
+pub struct SelectionMut<'a, V, S> {
+    lens: LensMut<'a, V>,
+    selector: S,
+}
+
+impl<'a, V, S: Selector> SelectionMut<'a, V, S> {
+    // Get the shape of the bounding LensMut
+    pub fn shape(&self) -> Shape {
+        self.lens.shape()
+    }
+
+    // Check whether a Lens-relative index is selected
+    pub fn contains<I: Into<Index>>(&self, index: I) -> bool {
+        let index = index.into();
+        self.shape().contains(index) && self.selector.contains(self.shape(), index)
+    }
+
+    // Get the number of selected cells
+    pub fn len(&self) -> usize {
+        self.as_selection().len()
+    }
+
+    // Check whether no cell is selected
+    pub fn is_empty(&self) -> bool {
+        self.as_selection().is_empty()
+    }
+
+    // Borrow the selection immutably
+    pub fn as_selection(&self) -> Selection<'_, V, &S> {
+        Selection {
+            lens: self.lens.as_lens(),
+            selector: &self.selector,
+        }
+    }
+
+    // Get a reference to a selected value
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<&V, MatricalError> {
+        let index = self.check(index.into())?;
+        self.lens.get(index)
+    }
+
+    // Get a mutable reference to a selected value
+    pub fn get_mut<I: Into<Index>>(&mut self, index: I) -> Result<&mut V, MatricalError> {
+        let index = self.check(index.into())?;
+        self.lens.get_mut(index)
+    }
+
+    // Replace a selected value, returning the previous value
+    pub fn set<I: Into<Index>>(&mut self, index: I, value: V) -> Result<V, MatricalError> {
+        Ok(std::mem::replace(self.get_mut(index)?, value))
+    }
+
+    // Iterate over the selected values in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &V> + '_ {
+        let view = self.lens.as_lens().view();
+        selected(self.shape(), &self.selector).map(move |index| IndexLonger::index(&view, (index.row(), index.col())))
+    }
+
+    // Iterate mutably over the selected values in row-major order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut V> + '_ {
+        self.indexed_iter_mut().map(|(_, value)| value)
+    }
+
+    // Iterate mutably over the selected values with their Lens-relative index
+    pub fn indexed_iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut V)> + '_ {
+        let shape = self.shape();
+        let selector = &self.selector;
+        // Peel one row at a time off the view so each row's span can be sliced
+        let mut rest = Some(self.lens.view_mut());
+        let rows = iter::from_fn(move || {
+            let view = rest.take().filter(|view| view.nrows() > 0)?;
+            let (lane, tail) = view.split_at(Axis(0), 1);
+            rest = Some(tail);
+            Some(lane.index_axis_move(Axis(0), 0))
+        });
+        rows.enumerate()
+            .flat_map(move |(row, lane)| {
+                let (span, scan) = row_cols(shape, selector, row);
+                let (start, step) = (span.cols.start, span.step);
+                lane.slice_move(s![span.cols; step as isize])
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(i, value)| (Index::new(row, start + i * step), value))
+                    .filter(move |(index, _)| !scan || selector.contains(shape, *index))
+            })
+    }
+
+    // Overwrite every selected value with `value`
+    pub fn fill(&mut self, value: V)
+    where
+        V: Clone,
+    {
+        self.iter_mut().for_each(|cell| *cell = value.clone());
+    }
+
+    // Modify every selected value in place
+    pub fn map_inplace<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut V),
+    {
+        self.iter_mut().for_each(f);
+    }
+
+    fn check(&self, index: Index) -> Result<Index, MatricalError> {
+        if self.contains(index) {
+            Ok(index)
+        } else {
+            Err(MatricalError::IndexOutOfBounds {
+                index,
+                shape: self.shape(),
+            })
+        }
+    }
+}
+
+// Get the columns of `row` to visit, clamped to `shape`, and whether each of
+// them still has to be checked with `contains`
+fn row_cols<S: Selector>(shape: Shape, selector: &S, row: usize) -> (RowSpan, bool) {
+    match selector.row_span(shape, row) {
+        Some(span) => {
+            let end = span.cols.end.min(shape.cols());
+            let start = span.cols.start.min(end);
+            let step = span.step.max(1);
+            (RowSpan { cols: start..end, step }, false)
+        }
+        None => (RowSpan::new(0..shape.cols()), true),
+    }
+}
+
+// Iterate over the indices of `shape` contained by `selector` in row-major order
+fn selected<S: Selector>(shape: Shape, selector: &S) -> impl Iterator<Item = Index> + '_ {
+    (0..shape.rows()).flat_map(move |row| {
+        let (span, scan) = row_cols(shape, selector, row);
+        span.cols
+            .step_by(span.step)
+            .map(move |col| Index::new(row, col))
+            .filter(move |index| !scan || selector.contains(shape, *index))
+    })
+}
+
+// Count the indices of `shape` contained by `selector`
+fn selected_len<S: Selector>(shape: Shape, selector: &S) -> usize {
+    (0..shape.rows())
+        .map(|row| match row_cols(shape, selector, row) {
+            (span, false) => span.cols.len().div_ceil(span.step),
+            (span, true) => span.cols.filter(|&col| selector.contains(shape, Index::new(row, col))).count(),
+        })
+        .sum()
+}
+
+impl<S: Selector> Selector for &S {
+    fn contains(&self, shape: Shape, index: Index) -> bool {
+        (**self).contains(shape, index)
+    }
+
+    fn row_span(&self, shape: Shape, row: usize) -> Option<RowSpan> {
+        (**self).row_span(shape, row)
+    }
+
+    fn validate(&self) -> Result<(), MatricalError> {
+        (**self).validate()
+    }
+}
+
+impl<'a, V> Lens<'a, V> {
+    // Select the cells of this Lens contained by `selector`
+    pub fn select<S: Selector>(&self, selector: S) -> Result<Selection<'a, V, S>, MatricalError> {
+        selector.validate()?;
+        Ok(Selection {
+            lens: self.reborrow(),
+            selector,
+        })
+    }
+}
+
+impl<'a, V> LensMut<'a, V> {
+    // Mutably select the cells of this LensMut contained by `selector`
+    pub fn select_mut<S: Selector>(&mut self, selector: S) -> Result<SelectionMut<'_, V, S>, MatricalError> {
+        selector.validate()?;
+        Ok(SelectionMut {
+            lens: self.reborrow(),
+            selector,
+        })
+    }
+
+    // Turn this LensMut into a mutable selection, keeping the original borrow
+    pub fn into_selection<S: Selector>(self, selector: S) -> Result<SelectionMut<'a, V, S>, MatricalError> {
+        selector.validate()?;
+        Ok(SelectionMut { lens: self, selector })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::matrix::Matrix;
+
+    fn matrix() -> Matrix<i32> {
+        Matrix::from_fn((3, 4), |row, col| (row * 10 + col) as i32).unwrap()
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let matrix = matrix();
+        let lens = matrix.as_lens();
+        assert_eq!(lens.select(Diagonal(0)).unwrap().to_vec(), vec![0, 11, 22]);
+        assert_eq!(lens.select(Diagonal(2)).unwrap().to_vec(), vec![2, 13]);
+        assert_eq!(lens.select(Diagonal(-1)).unwrap().to_vec(), vec![10, 21]);
+        assert!(lens.select(Diagonal(-3)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_band() {
+        let matrix = matrix();
+        let band = matrix.as_lens().select(Band { lower: 1, upper: 0 }).unwrap();
+        assert_eq!(band.to_vec(), vec![0, 10, 11, 21, 22]);
+        assert!(band.contains((2, 1)));
+        assert!(!band.contains((0, 1)));
+    }
+
+    #[test]
+    fn test_triangles() {
+        let matrix = matrix();
+        let lens = matrix.as_lens();
+        let upper = lens.select(UpperTriangle).unwrap();
+        assert_eq!(upper.len(), 9);
+        assert_eq!(upper.to_vec(), vec![0, 1, 2, 3, 11, 12, 13, 22, 23]);
+
+        let lower = lens.select(LowerTriangle).unwrap();
+        assert_eq!(lower.to_vec(), vec![0, 10, 11, 20, 21, 22]);
+        assert!(matches!(lower.get((0, 1)), Err(MatricalError::IndexOutOfBounds { .. })));
+        assert_eq!(*lower.get((2, 1)).unwrap(), 21);
+    }
+
+    #[test]
+    fn test_strided() {
+        let matrix = matrix();
+        let lens = matrix.as_lens();
+        let strided = lens.select(Strided { row_step: 2, col_step: 3 }).unwrap();
+        let indexed: Vec<((usize, usize), i32)> = strided.indexed_iter().map(|(i, v)| (i.into(), *v)).collect();
+        assert_eq!(indexed, vec![((0, 0), 0), ((0, 3), 3), ((2, 0), 20), ((2, 3), 23)]);
+
+        assert!(lens.select(Strided { row_step: 0, col_step: 1 }).is_err());
+    }
+
+    // A Selector without row spans, scanned cell by cell
+    struct Checkerboard;
+
+    impl Selector for Checkerboard {
+        fn contains(&self, _shape: Shape, index: Index) -> bool {
+            (index.row() + index.col()).is_multiple_of(2)
+        }
+    }
+
+    #[test]
+    fn test_row_spans_agree_with_contains() {
+        let shape = Shape::new(5, 7).unwrap();
+        fn scanned<S: Selector>(shape: Shape, selector: &S) -> Vec<Index> {
+            shape.region().iter().filter(|index| selector.contains(shape, *index)).collect()
+        }
+        fn check<S: Selector>(shape: Shape, selector: S) {
+            let expected = scanned(shape, &selector);
+            assert_eq!(selected(shape, &selector).collect::<Vec<_>>(), expected);
+            assert_eq!(selected_len(shape, &selector), expected.len());
+        }
+        for k in -6..8 {
+            check(shape, Diagonal(k));
+        }
+        check(shape, Band { lower: 2, upper: 1 });
+        check(shape, Band { lower: 0, upper: usize::MAX });
+        check(shape, UpperTriangle);
+        check(shape, LowerTriangle);
+        check(shape, Strided { row_step: 2, col_step: 3 });
+        check(shape, Checkerboard);
+
+        let mut matrix = matrix();
+        let mut selection = matrix.as_lens_mut().into_selection(Checkerboard).unwrap();
+        selection.fill(-1);
+        assert_eq!(selection.len(), 6);
+        assert_eq!(*matrix.get((1, 1)).unwrap(), -1);
+        assert_eq!(*matrix.get((1, 2)).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_selection_on_nested_lens_is_relative() {
+        let matrix = matrix();
+        let lens = matrix
+            .lens(crate::Region::inclusive((1, 1).into(), (2, 3).into()).unwrap())
+            .unwrap();
+        assert_eq!(lens.select(Diagonal(0)).unwrap().to_vec(), vec![11, 22]);
+    }
+
+    #[test]
+    fn test_selection_mut_writes_only_selected_cells() {
+        let mut matrix: Matrix<i32> = Matrix::zeros((3, 3)).unwrap();
+        let mut lens = matrix.as_lens_mut();
+
+        lens.select_mut(UpperTriangle).unwrap().fill(1);
+        lens.select_mut(Diagonal(0)).unwrap().map_inplace(|value| *value += 1);
+        let mut lower = lens.select_mut(LowerTriangle).unwrap();
+        assert!(lower.set((0, 2), 9).is_err());
+        lower.set((2, 0), 5).unwrap();
+        assert_eq!(lower.iter().copied().collect::<Vec<_>>(), vec![2, 0, 2, 5, 0, 2]);
+
+        let values: Vec<i32> = matrix.iter().copied().collect();
+        assert_eq!(values, vec![2, 1, 1, 0, 2, 1, 5, 0, 2]);
+    }
+
+    #[test]
+    fn test_selection_mut_iter_mut() {
+        let mut matrix = matrix();
+        let mut selection = matrix.as_lens_mut().into_selection(Band { lower: 0, upper: 1 }).unwrap();
+        for (index, value) in selection.indexed_iter_mut() {
+            *value = -((index.row() * 10 + index.col()) as i32);
+        }
+        assert_eq!(selection.len(), 6);
+
+        assert_eq!(*matrix.get((1, 2)).unwrap(), -12);
+        assert_eq!(*matrix.get((1, 0)).unwrap(), 10);
+        assert_eq!(*matrix.get((0, 3)).unwrap(), 3);
+    }
+}
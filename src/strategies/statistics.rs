@@ -0,0 +1,470 @@
+use crate::error::MatricalError;
+use crate::operations::aggregate::{KahanSum, Welford};
+use crate::schematics::shape::Axis;
+use crate::strategies::cog::{Cog, NoPolicy};
+use crate::strategies::gear::GearMut;
+use crate::strategies::lens::LensMut;
+use crate::strategies::report::OperationId;
+
+use ndarray::ArrayViewMut1;
+use num_traits::Float;
+
+use std::cmp::Ordering;
+
+// Built-in statistical Gears
+//
+// Every Gear in this module works lane by lane: with `Axis::Column` each
+// column of the Lens is transformed on its own, with `Axis::Row` each row is.
+// Lane statistics are computed from the values inside the Lens only. A lane
+// that contains NaN has no meaningful mean, median or range, so the scaling
+// Gears write NaN across that whole lane instead of guessing. Requirements
+// that depend only on the Lens shape are checked before anything is written,
+// so a failing Gear leaves its target untouched.
+//
+
+// Convert a count into the float type of a lane
+pub(crate) fn cast<V: Float>(count: usize) -> V {
+    V::from(count).unwrap_or_else(V::nan)
+}
+
+// Get the arithmetic mean of a lane from its compensated sum, or NaN when it is empty
+pub(crate) fn mean<V: Float>(values: &[V]) -> V {
+    values.iter().copied().collect::<KahanSum<V>>().sum() / cast(values.len())
+}
+
+// Get the variance of a lane with `ddof` delta degrees of freedom
+pub(crate) fn variance<V: Float>(values: &[V], ddof: usize) -> V {
+    values.iter().copied().collect::<Welford<V>>().variance(ddof)
+}
+
+// Get the median of a lane, or NaN when it is empty or contains NaN
+pub(crate) fn median<V: Float>(values: &[V]) -> V {
+    quantile(values, V::from(0.5).unwrap_or_else(V::nan))
+}
+
+// Get the `q`-th quantile of a lane by linear interpolation between order
+// statistics, or NaN when it is empty or contains NaN
+pub(crate) fn quantile<V: Float>(values: &[V], q: V) -> V {
+    if values.is_empty() || values.iter().any(|value| value.is_nan()) {
+        return V::nan();
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let position = q * cast(sorted.len() - 1);
+    let lower = position.floor();
+    let index = lower.to_usize().unwrap_or(0).min(sorted.len() - 1);
+    let next = (index + 1).min(sorted.len() - 1);
+    sorted[index] + (sorted[next] - sorted[index]) * (position - lower)
+}
+
+// Run `f` over every lane of `target` along `axis`
+fn for_each_lane<V, F>(target: &mut LensMut<'_, V>, axis: Axis, mut f: F)
+where
+    V: Float,
+    F: FnMut(ArrayViewMut1<'_, V>),
+{
+    let mut view = target.view_mut();
+    for lane in view.lanes_mut(axis.lane_axis()) {
+        f(lane);
+    }
+}
+
+// Rescale every lane as `(value - center) / scale`, where `stats` returns the
+// (center, scale) of a lane; a zero scale is treated as one so constant lanes
+// map to zero instead of dividing by zero
+fn rescale_lanes<V, F>(target: &mut LensMut<'_, V>, axis: Axis, stats: F)
+where
+    V: Float,
+    F: Fn(&[V]) -> (V, V),
+{
+    for_each_lane(target, axis, |mut lane| {
+        let values = lane.to_vec();
+        if values.iter().any(|value| value.is_nan()) {
+            lane.fill(V::nan());
+            return;
+        }
+        let (center, scale) = stats(&values);
+        let scale = if scale == V::zero() { V::one() } else { scale };
+        lane.mapv_inplace(|value| (value - center) / scale);
+    });
+}
+
+// Get the number of values in one lane of `target`
+fn lane_len<V>(target: &LensMut<'_, V>, axis: Axis) -> usize {
+    match axis {
+        Axis::Column => target.rows(),
+        Axis::Row => target.cols(),
+    }
+}
+
+// The Ddof struct, the delta degrees of freedom used when estimating spread:
+// 0 for the population standard deviation, 1 for the sample standard deviation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Ddof(pub usize);
+
+impl Cog for Ddof {
+    fn id(&self) -> String {
+        format!("ddof={}", self.0)
+    }
+}
+
+// The ZScore struct, standardizing each lane to zero mean and unit standard deviation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZScore {
+    axis: Axis,
+}
+
+impl ZScore {
+    // Create a new ZScore
+    pub fn new(axis: Axis) -> Self {
+        Self { axis }
+    }
+}
+
+impl<V: Float> GearMut<V> for ZScore {
+    type Policy = Ddof;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, policy: &Ddof) -> Result<(), MatricalError> {
+        let Ddof(ddof) = *policy;
+        let len = lane_len(target, self.axis);
+        if len > 0 && len <= ddof {
+            return Err(MatricalError::InsufficientData {
+                operation: "z_score",
+                required: ddof + 1,
+                actual: len,
+            });
+        }
+        rescale_lanes(target, self.axis, |values| {
+            (mean(values), variance(values, ddof).sqrt())
+        });
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("z_score", 1)
+    }
+}
+
+// The MinMax struct, mapping each lane's minimum and maximum onto a target range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinMax<V> {
+    axis: Axis,
+    low: V,
+    high: V,
+}
+
+impl<V: Float> MinMax<V> {
+    // Create a new MinMax scaling each lane into [0, 1]
+    pub fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            low: V::zero(),
+            high: V::one(),
+        }
+    }
+
+    // Set the target range; `low` must be below `high`
+    pub fn range(mut self, low: V, high: V) -> Self {
+        self.low = low;
+        self.high = high;
+        self
+    }
+}
+
+impl<V: Float> GearMut<V> for MinMax<V> {
+    type Policy = NoPolicy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        if self.low.is_nan() || self.high.is_nan() || self.low >= self.high {
+            return Err(MatricalError::InvalidValue {
+                operation: "min_max",
+                index: None,
+            });
+        }
+        rescale_lanes(target, self.axis, |values| {
+            let min = values.iter().copied().fold(V::infinity(), V::min);
+            let max = values.iter().copied().fold(V::neg_infinity(), V::max);
+            (min, max - min)
+        });
+        let width = self.high - self.low;
+        let low = self.low;
+        target.map_inplace(|value| *value = low + *value * width);
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("min_max", 1)
+    }
+}
+
+// The RobustScale struct, centering each lane on its median and scaling by its
+// median absolute deviation (MAD)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RobustScale<V> {
+    axis: Axis,
+    // Factor applied to the MAD before dividing
+    factor: V,
+}
+
+impl<V: Float> RobustScale<V> {
+    // Create a new RobustScale dividing by the raw MAD
+    pub fn new(axis: Axis) -> Self {
+        Self { axis, factor: V::one() }
+    }
+
+    // Scale the MAD by 1.4826 so it estimates the standard deviation of normal data
+    pub fn normal_consistent(mut self) -> Self {
+        self.factor = V::from(1.4826).unwrap_or_else(V::one);
+        self
+    }
+}
+
+impl<V: Float> GearMut<V> for RobustScale<V> {
+    type Policy = NoPolicy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        let factor = self.factor;
+        rescale_lanes(target, self.axis, |values| {
+            let center = median(values);
+            let deviations: Vec<V> = values.iter().map(|value| (*value - center).abs()).collect();
+            (center, median(&deviations) * factor)
+        });
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("robust_scale", 1)
+    }
+}
+
+// The MeanCenter struct, subtracting each lane's mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeanCenter {
+    axis: Axis,
+}
+
+impl MeanCenter {
+    // Create a new MeanCenter
+    pub fn new(axis: Axis) -> Self {
+        Self { axis }
+    }
+}
+
+impl<V: Float> GearMut<V> for MeanCenter {
+    type Policy = NoPolicy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        rescale_lanes(target, self.axis, |values| (mean(values), V::one()));
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("mean_center", 1)
+    }
+}
+
+// The Rank struct, replacing each value with its 1-based rank within its lane
+//
+// Tied values share the average of the ranks they span, and NaN values are
+// left as NaN and excluded from the ranking.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rank {
+    axis: Axis,
+}
+
+impl Rank {
+    // Create a new Rank
+    pub fn new(axis: Axis) -> Self {
+        Self { axis }
+    }
+}
+
+impl<V: Float> GearMut<V> for Rank {
+    type Policy = NoPolicy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        for_each_lane(target, self.axis, |mut lane| {
+            let mut order: Vec<(usize, V)> = lane
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, value)| !value.is_nan())
+                .collect();
+            order.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+
+            let mut start = 0;
+            while start < order.len() {
+                let mut end = start + 1;
+                while end < order.len() && order[end].1 == order[start].1 {
+                    end += 1;
+                }
+                // Positions start..end hold ranks start + 1 ..= end
+                let rank = cast::<V>(start + 1 + end) / cast(2);
+                for &(position, _) in &order[start..end] {
+                    lane[position] = rank;
+                }
+                start = end;
+            }
+        });
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("rank", 1)
+    }
+}
+
+// The Difference struct, replacing each value with its difference from the
+// previous value in the lane, applied `order` times
+//
+// The first `order` values of each lane have no predecessor and become NaN.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Difference {
+    axis: Axis,
+    order: usize,
+}
+
+impl Difference {
+    // Create a new Difference of the given order
+    pub fn new(axis: Axis, order: usize) -> Self {
+        Self { axis, order }
+    }
+
+    // Create a new first-order Difference
+    pub fn first(axis: Axis) -> Self {
+        Self::new(axis, 1)
+    }
+
+    // Create a new second-order Difference
+    pub fn second(axis: Axis) -> Self {
+        Self::new(axis, 2)
+    }
+}
+
+impl<V: Float> GearMut<V> for Difference {
+    type Policy = NoPolicy;
+
+    fn apply_mut(&self, target: &mut LensMut<'_, V>, _policy: &NoPolicy) -> Result<(), MatricalError> {
+        if self.order == 0 {
+            return Err(MatricalError::InvalidValue {
+                operation: "difference",
+                index: None,
+            });
+        }
+        let order = self.order;
+        for_each_lane(target, self.axis, |mut lane| {
+            for _ in 0..order {
+                for position in (1..lane.len()).rev() {
+                    lane[position] = lane[position] - lane[position - 1];
+                }
+                if let Some(first) = lane.get_mut(0) {
+                    *first = V::nan();
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        OperationId::new("difference", 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematics::matrix::Matrix;
+    use crate::schematics::shape::Region;
+
+    fn assert_close(actual: &Matrix<f64>, expected: &[f64]) {
+        let actual: Vec<f64> = actual.iter().copied().collect();
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a.is_nan() && e.is_nan()) || (a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    fn columns() -> Matrix<f64> {
+        Matrix::from_shape_vec((4, 2), vec![1.0, 10.0, 2.0, 10.0, 3.0, 10.0, 4.0, 10.0]).unwrap()
+    }
+
+    #[test]
+    fn test_z_score_columns() {
+        let mut matrix = columns();
+        ZScore::new(Axis::Column).apply_mut(&mut matrix.as_lens_mut(), &Ddof(0)).unwrap();
+        let s = 1.25f64.sqrt();
+        assert_close(&matrix, &[-1.5 / s, 0.0, -0.5 / s, 0.0, 0.5 / s, 0.0, 1.5 / s, 0.0]);
+    }
+
+    #[test]
+    fn test_z_score_rows_with_ddof() {
+        let mut matrix = Matrix::from_shape_vec((1, 3), vec![1.0, 2.0, 3.0]).unwrap();
+        ZScore::new(Axis::Row).apply_mut(&mut matrix.as_lens_mut(), &Ddof(1)).unwrap();
+        assert_close(&matrix, &[-1.0, 0.0, 1.0]);
+
+        let mut single = Matrix::from_shape_vec((1, 3), vec![1.0, 2.0, 3.0]).unwrap();
+        assert!(matches!(
+            ZScore::new(Axis::Column).apply_mut(&mut single.as_lens_mut(), &Ddof(1)),
+            Err(MatricalError::InsufficientData { required: 2, actual: 1, .. })
+        ));
+        assert_eq!(single.iter().copied().collect::<Vec<_>>(), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let mut matrix = columns();
+        MinMax::new(Axis::Column).range(-1.0, 1.0).apply_mut(&mut matrix.as_lens_mut(), &NoPolicy).unwrap();
+        assert_close(&matrix, &[-1.0, -1.0, -1.0 / 3.0, -1.0, 1.0 / 3.0, -1.0, 1.0, -1.0]);
+
+        assert!(MinMax::new(Axis::Row).range(1.0, 1.0).apply_mut(&mut matrix.as_lens_mut(), &NoPolicy).is_err());
+    }
+
+    #[test]
+    fn test_robust_scale() {
+        let mut matrix = Matrix::from_shape_vec((5, 1), vec![1.0, 2.0, 3.0, 4.0, 100.0]).unwrap();
+        RobustScale::new(Axis::Column).apply_mut(&mut matrix.as_lens_mut(), &NoPolicy).unwrap();
+        assert_close(&matrix, &[-2.0, -1.0, 0.0, 1.0, 97.0]);
+    }
+
+    #[test]
+    fn test_nan_lane_propagates() {
+        let mut matrix = Matrix::from_shape_vec((2, 2), vec![1.0, f64::NAN, 3.0, 4.0]).unwrap();
+        MeanCenter::new(Axis::Column).apply_mut(&mut matrix.as_lens_mut(), &NoPolicy).unwrap();
+        assert_close(&matrix, &[-1.0, f64::NAN, 1.0, f64::NAN]);
+    }
+
+    #[test]
+    fn test_rank_averages_ties() {
+        let mut matrix = Matrix::from_shape_vec((1, 5), vec![3.0, 1.0, 3.0, f64::NAN, 2.0]).unwrap();
+        Rank::new(Axis::Row).apply_mut(&mut matrix.as_lens_mut(), &NoPolicy).unwrap();
+        assert_close(&matrix, &[3.5, 1.0, 3.5, f64::NAN, 2.0]);
+    }
+
+    #[test]
+    fn test_differences() {
+        let mut first = Matrix::from_shape_vec((4, 1), vec![1.0, 4.0, 9.0, 16.0]).unwrap();
+        let mut second = first.clone();
+        Difference::first(Axis::Column).apply_mut(&mut first.as_lens_mut(), &NoPolicy).unwrap();
+        Difference::second(Axis::Column).apply_mut(&mut second.as_lens_mut(), &NoPolicy).unwrap();
+        assert_close(&first, &[f64::NAN, 3.0, 5.0, 7.0]);
+        assert_close(&second, &[f64::NAN, f64::NAN, 2.0, 2.0]);
+        assert!(Difference::new(Axis::Row, 0).apply_mut(&mut first.as_lens_mut(), &NoPolicy).is_err());
+    }
+
+    #[test]
+    fn test_gear_stays_inside_lens() {
+        let mut matrix = columns();
+        let region = Region::new((1, 0).into(), (3, 1).into()).unwrap();
+        MeanCenter::new(Axis::Column).apply_mut(&mut matrix.lens_mut(region).unwrap(), &NoPolicy).unwrap();
+        assert_close(&matrix, &[1.0, 10.0, -0.5, 10.0, 0.5, 10.0, 4.0, 10.0]);
+    }
+
+    #[test]
+    fn test_quantile() {
+        assert_eq!(quantile(&[4.0, 1.0, 3.0, 2.0], 0.25), 1.75);
+        assert_eq!(median(&[5.0, 1.0, 3.0]), 3.0);
+        assert!(median::<f64>(&[]).is_nan());
+    }
+}
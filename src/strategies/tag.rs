@@ -1,17 +1,268 @@
+use crate::error::MatricalError;
 
+use serde::{Deserialize, Serialize};
 
+use std::fmt;
 
+// The longest key name, in bytes, a custom Tag key may have
+pub const MAX_TAG_KEY_LEN: usize = 64;
 
-// Defines Tag (Attribute) that can be applied to a given Element, Matrix, or Vector. Generally issued 
-// by Cog to provide additional context for operations performed on the Element, Matrix, or Vector, however
-// can serve many other purposes. Such as data profiling, data validation, and/or data transformation in
-// effort to support the various operations performed within Matrical.
+// The longest value, in bytes, a Tag may carry
+pub const MAX_TAG_VALUE_LEN: usize = 256;
+
+// The most Tags a single TagSet may hold
+pub const MAX_TAGS: usize = 32;
+
+// The key of a Tag
+//
+// Well-known keys cover the provenance Matrical itself records; anything else
+// is a bounded custom key supplied by the consumer.
+//
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TagKey {
+    // Identifier of the consumer's feature schema
+    FeatureSchema,
+    // Unit of measurement
+    Unit,
+    // Identifier of the derivation that produced the data
+    Derivation,
+    // Identifier of the Cog policy applied
+    Policy,
+    Custom(String),
+}
+
+impl fmt::Display for TagKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TagKey::FeatureSchema => write!(f, "feature_schema"),
+            TagKey::Unit => write!(f, "unit"),
+            TagKey::Derivation => write!(f, "derivation"),
+            TagKey::Policy => write!(f, "policy"),
+            TagKey::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+// Defines Tag (Attribute) that can be applied to a Matrix, to one of its rows or columns, or to the
+// result of an operation. A Tag records metadata or provenance as a key and a bounded value; it never
+// carries data or instructions, so reading a Tag cannot change what an operation does to the Matrix.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedTag")]
 pub struct Tag {
 
-    // The name of the Tag
-    name: String,
+    // The key of the Tag
+    key: TagKey,
+
+    // The value of the Tag
+    value: String,
+
+}
+
+impl Tag {
+    // Create a new Tag, rejecting empty or oversized keys and oversized values
+    pub fn new(key: TagKey, value: impl Into<String>) -> Result<Self, MatricalError> {
+        let value = value.into();
+        if let TagKey::Custom(name) = &key {
+            if name.is_empty() || name.len() > MAX_TAG_KEY_LEN {
+                return Err(MatricalError::TagLimitExceeded {
+                    limit: "key length",
+                    max: MAX_TAG_KEY_LEN,
+                    actual: name.len(),
+                });
+            }
+        }
+        if value.len() > MAX_TAG_VALUE_LEN {
+            return Err(MatricalError::TagLimitExceeded {
+                limit: "value length",
+                max: MAX_TAG_VALUE_LEN,
+                actual: value.len(),
+            });
+        }
+        Ok(Self { key, value })
+    }
+
+    // Create a new feature-schema Tag
+    pub fn feature_schema(id: impl Into<String>) -> Result<Self, MatricalError> {
+        Self::new(TagKey::FeatureSchema, id)
+    }
+
+    // Create a new unit Tag
+    pub fn unit(unit: impl Into<String>) -> Result<Self, MatricalError> {
+        Self::new(TagKey::Unit, unit)
+    }
+
+    // Create a new derivation Tag
+    pub fn derivation(id: impl Into<String>) -> Result<Self, MatricalError> {
+        Self::new(TagKey::Derivation, id)
+    }
+
+    // Create a new policy Tag
+    pub fn policy(id: impl Into<String>) -> Result<Self, MatricalError> {
+        Self::new(TagKey::Policy, id)
+    }
+
+    // Create a new Tag with a custom key
+    pub fn custom(key: impl Into<String>, value: impl Into<String>) -> Result<Self, MatricalError> {
+        Self::new(TagKey::Custom(key.into()), value)
+    }
+
+    // Get the key of the Tag
+    pub fn key(&self) -> &TagKey {
+        &self.key
+    }
+
+    // Get the value of the Tag
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+// Deserialized Tags go through the same bounds checks as constructed ones
+#[derive(Deserialize)]
+struct UncheckedTag {
+    key: TagKey,
+    value: String,
+}
+
+impl TryFrom<UncheckedTag> for Tag {
+    type Error = MatricalError;
+
+    fn try_from(tag: UncheckedTag) -> Result<Self, Self::Error> {
+        Tag::new(tag.key, tag.value)
+    }
+}
+
+// A bounded set of Tags holding at most one Tag per key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Tag>", into = "Vec<Tag>")]
+pub struct TagSet {
+    tags: Vec<Tag>,
+}
+
+impl TagSet {
+    // Create a new, empty TagSet
+    pub fn new() -> Self {
+        Self { tags: Vec::new() }
+    }
+
+    // Add a Tag, returning the Tag it replaces under the same key
+    pub fn insert(&mut self, tag: Tag) -> Result<Option<Tag>, MatricalError> {
+        if let Some(existing) = self.tags.iter_mut().find(|existing| existing.key == tag.key) {
+            return Ok(Some(std::mem::replace(existing, tag)));
+        }
+        if self.tags.len() == MAX_TAGS {
+            return Err(MatricalError::TagLimitExceeded {
+                limit: "tag count",
+                max: MAX_TAGS,
+                actual: MAX_TAGS + 1,
+            });
+        }
+        self.tags.push(tag);
+        Ok(None)
+    }
 
-} 
+    // Add a Tag, builder style
+    pub fn with(mut self, tag: Tag) -> Result<Self, MatricalError> {
+        self.insert(tag)?;
+        Ok(self)
+    }
+
+    // Remove the Tag with the given key
+    pub fn remove(&mut self, key: &TagKey) -> Option<Tag> {
+        let position = self.tags.iter().position(|tag| &tag.key == key)?;
+        Some(self.tags.remove(position))
+    }
+
+    // Get the Tag with the given key
+    pub fn get(&self, key: &TagKey) -> Option<&Tag> {
+        self.tags.iter().find(|tag| &tag.key == key)
+    }
+
+    // Check whether this exact Tag, key and value, is present
+    pub fn contains(&self, tag: &Tag) -> bool {
+        self.get(&tag.key) == Some(tag)
+    }
+
+    // Get the number of Tags
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    // Check whether the TagSet is empty
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    // Iterate over the Tags in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &Tag> {
+        self.tags.iter()
+    }
+}
+
+impl TryFrom<Vec<Tag>> for TagSet {
+    type Error = MatricalError;
+
+    fn try_from(tags: Vec<Tag>) -> Result<Self, Self::Error> {
+        tags.into_iter().try_fold(TagSet::new(), TagSet::with)
+    }
+}
+
+impl From<TagSet> for Vec<Tag> {
+    fn from(set: TagSet) -> Self {
+        set.tags
+    }
+}
+
+// Attaches a TagSet to the result of an operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tagged<T> {
+    value: T,
+    tags: TagSet,
+}
+
+impl<T> Tagged<T> {
+    // Wrap a value with no Tags
+    pub fn new(value: T) -> Self {
+        Self { value, tags: TagSet::new() }
+    }
+
+    // Wrap a value with the given Tags
+    pub fn with_tags(value: T, tags: TagSet) -> Self {
+        Self { value, tags }
+    }
+
+    // Add a Tag, builder style
+    pub fn tag(mut self, tag: Tag) -> Result<Self, MatricalError> {
+        self.tags.insert(tag)?;
+        Ok(self)
+    }
+
+    // Get the wrapped value
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    // Get the Tags
+    pub fn tags(&self) -> &TagSet {
+        &self.tags
+    }
+
+    // Mutably get the Tags
+    pub fn tags_mut(&mut self) -> &mut TagSet {
+        &mut self.tags
+    }
+
+    // Consume the wrapper and return the value and its Tags
+    pub fn into_parts(self) -> (T, TagSet) {
+        (self.value, self.tags)
+    }
+}
 
 // Defines a parameterized query that can be used to perform various operations on a given data set.
 //
@@ -34,3 +285,67 @@ pub struct ParameterizedQuery {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_bounds() {
+        assert!(Tag::unit("ms").is_ok());
+        assert!(matches!(
+            Tag::custom("", "x"),
+            Err(MatricalError::TagLimitExceeded { limit: "key length", .. })
+        ));
+        assert!(Tag::custom("k".repeat(MAX_TAG_KEY_LEN + 1), "x").is_err());
+        assert!(matches!(
+            Tag::derivation("v".repeat(MAX_TAG_VALUE_LEN + 1)),
+            Err(MatricalError::TagLimitExceeded { max: MAX_TAG_VALUE_LEN, .. })
+        ));
+        assert_eq!(Tag::custom("session", "a").unwrap().to_string(), "session=a");
+    }
+
+    #[test]
+    fn test_tag_set_one_value_per_key() {
+        let mut set = TagSet::new();
+        assert_eq!(set.insert(Tag::unit("ms").unwrap()).unwrap(), None);
+        let replaced = set.insert(Tag::unit("s").unwrap()).unwrap();
+        assert_eq!(replaced, Some(Tag::unit("ms").unwrap()));
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&Tag::unit("s").unwrap()));
+        assert!(!set.contains(&Tag::unit("ms").unwrap()));
+        assert_eq!(set.get(&TagKey::Unit).unwrap().value(), "s");
+        assert!(set.remove(&TagKey::Unit).is_some());
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_tag_set_is_bounded() {
+        let mut set = TagSet::new();
+        for i in 0..MAX_TAGS {
+            set.insert(Tag::custom(format!("k{}", i), "v").unwrap()).unwrap();
+        }
+        assert!(set.insert(Tag::custom("one_more", "v").unwrap()).is_err());
+        // Replacing an existing key is still allowed when full
+        assert!(set.insert(Tag::custom("k0", "w").unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_checks_bounds() {
+        let json = serde_json::to_string(&Tag::feature_schema("typing-v3").unwrap()).unwrap();
+        let tag: Tag = serde_json::from_str(&json).unwrap();
+        assert_eq!(tag.key(), &TagKey::FeatureSchema);
+
+        let oversized = format!(r#"{{"key":"Unit","value":"{}"}}"#, "v".repeat(MAX_TAG_VALUE_LEN + 1));
+        assert!(serde_json::from_str::<Tag>(&oversized).is_err());
+    }
+
+    #[test]
+    fn test_tagged_result() {
+        let result = Tagged::new(4.0).tag(Tag::derivation("mean").unwrap()).unwrap();
+        assert_eq!(*result.value(), 4.0);
+        let (value, tags) = result.into_parts();
+        assert_eq!(value, 4.0);
+        assert_eq!(tags.get(&TagKey::Derivation).unwrap().value(), "mean");
+    }
+}
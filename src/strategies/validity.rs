@@ -0,0 +1,436 @@
+use crate::error::MatricalError;
+use crate::schematics::mask::Mask;
+use crate::schematics::masked::MaskedMatrix;
+use crate::schematics::matrix::Matrix;
+use crate::schematics::shape::{Axis, Index, Region, Shape};
+use crate::strategies::cog::Cog;
+use crate::strategies::gear::GearMut;
+use crate::strategies::lens::{Lens, LensMut};
+use crate::strategies::pipeline::combined;
+use crate::strategies::report::OperationId;
+
+use std::collections::BTreeMap;
+
+// A read-only view of a region of a MaskedMatrix
+//
+// Indices are relative to the Lens, exactly as for `Lens`; the validity of a
+// cell is looked up in the Mask of the whole MaskedMatrix at the same offset.
+//
+#[derive(Debug)]
+pub struct MaskedLens<'a, V> {
+    lens: Lens<'a, V>,
+    valid: &'a Mask,
+}
+
+impl<'a, V> MaskedLens<'a, V> {
+    // Get the Lens over the values, including placeholders under invalid cells
+    pub fn lens(&self) -> &Lens<'a, V> {
+        &self.lens
+    }
+
+    // Get the region of the MaskedMatrix this view covers
+    pub fn region(&self) -> Region {
+        self.lens.region()
+    }
+
+    // Get the shape of the view
+    pub fn shape(&self) -> Shape {
+        self.lens.shape()
+    }
+
+    // Check whether a Lens-relative cell is valid
+    pub fn is_valid<I: Into<Index>>(&self, index: I) -> Result<bool, MatricalError> {
+        is_valid(self.valid, self.region(), index.into())
+    }
+
+    // Get the value of a Lens-relative cell, or `None` if it is invalid
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<Option<&'a V>, MatricalError> {
+        let index = index.into();
+        let value = self.lens.get(index)?;
+        Ok(self.is_valid(index)?.then_some(value))
+    }
+
+    // Iterate over the cells in row-major order, `None` for invalid cells
+    pub fn iter(&self) -> impl Iterator<Item = Option<&'a V>> + '_ {
+        self.lens
+            .indexed_iter()
+            .map(move |(index, value)| self.is_valid(index).ok()?.then_some(value))
+    }
+
+    // Get the number of valid cells
+    pub fn count_valid(&self) -> usize {
+        self.iter().filter(Option::is_some).count()
+    }
+
+    // Find the first invalid cell in row-major order
+    pub fn first_invalid(&self) -> Option<Index> {
+        first_invalid(self.valid, self.region())
+    }
+
+    // Copy the view into a new MaskedMatrix
+    pub fn to_masked_matrix(&self) -> MaskedMatrix<V>
+    where
+        V: Clone,
+    {
+        let region = self.region();
+        let valid = Mask::from_fn(self.shape(), |row, col| {
+            self.valid
+                .get((region.start().row() + row, region.start().col() + col))
+                .unwrap_or(false)
+        });
+        MaskedMatrix::new(self.lens.to_matrix(), valid).expect("the Mask is built with the Lens shape")
+    }
+}
+
+// A mutable view of a region of a MaskedMatrix
+//
+// Writing a value through the view marks its cell valid; placeholders under
+// invalid cells can only be replaced, never read.
+//
+#[derive(Debug)]
+pub struct MaskedLensMut<'a, V> {
+    lens: LensMut<'a, V>,
+    valid: &'a mut Mask,
+}
+
+impl<'a, V> MaskedLensMut<'a, V> {
+    // Borrow the view as a MaskedLens
+    pub fn as_masked_lens(&self) -> MaskedLens<'_, V> {
+        MaskedLens {
+            lens: self.lens.as_lens(),
+            valid: self.valid,
+        }
+    }
+
+    // Get the region of the MaskedMatrix this view covers
+    pub fn region(&self) -> Region {
+        self.lens.region()
+    }
+
+    // Get the shape of the view
+    pub fn shape(&self) -> Shape {
+        self.lens.shape()
+    }
+
+    // Check whether a Lens-relative cell is valid
+    pub fn is_valid<I: Into<Index>>(&self, index: I) -> Result<bool, MatricalError> {
+        is_valid(self.valid, self.region(), index.into())
+    }
+
+    // Get the value of a Lens-relative cell, or `None` if it is invalid
+    pub fn get<I: Into<Index>>(&self, index: I) -> Result<Option<&V>, MatricalError> {
+        let index = index.into();
+        let valid = self.is_valid(index)?;
+        Ok(valid.then_some(self.lens.get(index)?))
+    }
+
+    // Set the value of a Lens-relative cell and mark it valid
+    pub fn set<I: Into<Index>>(&mut self, index: I, value: V) -> Result<(), MatricalError> {
+        let index = index.into();
+        self.lens.set(index, value)?;
+        self.valid.set(absolute(self.region(), index), true)?;
+        Ok(())
+    }
+
+    // Mark a Lens-relative cell invalid
+    pub fn invalidate<I: Into<Index>>(&mut self, index: I) -> Result<(), MatricalError> {
+        let index = self.lens.shape().check_index(index.into())?;
+        self.valid.set(absolute(self.region(), index), false)?;
+        Ok(())
+    }
+
+    // Find the first invalid cell in row-major order
+    pub fn first_invalid(&self) -> Option<Index> {
+        first_invalid(self.valid, self.region())
+    }
+}
+
+// Check a Lens-relative index against `region` and look it up in `valid`
+fn is_valid(valid: &Mask, region: Region, index: Index) -> Result<bool, MatricalError> {
+    let index = region.shape().check_index(index)?;
+    valid.get(absolute(region, index))
+}
+
+// Translate an in-bounds Lens-relative index to an index of the MaskedMatrix
+fn absolute(region: Region, index: Index) -> Index {
+    Index::new(region.start().row() + index.row(), region.start().col() + index.col())
+}
+
+// Find the first invalid cell of `region`, as a Lens-relative index
+fn first_invalid(valid: &Mask, region: Region) -> Option<Index> {
+    region
+        .iter()
+        .find(|&index| !valid.get(index).unwrap_or(false))
+        .map(|index| Index::new(index.row() - region.start().row(), index.col() - region.start().col()))
+}
+
+impl<V> MaskedMatrix<V> {
+    // Create a MaskedLens over a region
+    pub fn lens(&self, region: Region) -> Result<MaskedLens<'_, V>, MatricalError> {
+        Ok(MaskedLens {
+            lens: self.values().lens(region)?,
+            valid: self.valid(),
+        })
+    }
+
+    // Create a MaskedLens over the whole MaskedMatrix
+    pub fn as_lens(&self) -> MaskedLens<'_, V> {
+        MaskedLens {
+            lens: self.values().as_lens(),
+            valid: self.valid(),
+        }
+    }
+
+    // Create a MaskedLensMut over a region
+    pub fn lens_mut(&mut self, region: Region) -> Result<MaskedLensMut<'_, V>, MatricalError> {
+        let (values, valid) = self.parts_mut();
+        Ok(MaskedLensMut {
+            lens: values.lens_mut(region)?,
+            valid,
+        })
+    }
+
+    // Create a MaskedLensMut over the whole MaskedMatrix
+    pub fn as_lens_mut(&mut self) -> MaskedLensMut<'_, V> {
+        let (values, valid) = self.parts_mut();
+        MaskedLensMut {
+            lens: values.as_lens_mut(),
+            valid,
+        }
+    }
+}
+
+// The InvalidCells Cog, deciding what a masked Gear does about invalid cells
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum InvalidCells {
+    // Fail with `InvalidValue` at the first invalid cell
+    #[default]
+    Reject,
+    // Apply the Gear to the valid cells of each lane only; invalid cells stay untouched and invalid
+    Skip,
+    // Invalidate every lane containing an invalid cell and apply the Gear to the complete lanes
+    Propagate,
+}
+
+impl Cog for InvalidCells {
+    fn id(&self) -> String {
+        match self {
+            InvalidCells::Reject => "invalid=reject".to_string(),
+            InvalidCells::Skip => "invalid=skip".to_string(),
+            InvalidCells::Propagate => "invalid=propagate".to_string(),
+        }
+    }
+}
+
+// The MaskedGearMut trait, the GearMut counterpart for MaskedLensMut targets
+pub trait MaskedGearMut<V> {
+    type Policy: Cog;
+
+    fn apply_masked(&self, target: &mut MaskedLensMut<'_, V>, policy: &Self::Policy) -> Result<(), MatricalError>;
+
+    fn operation(&self) -> OperationId;
+}
+
+// The Masked struct
+//
+// Adapts any GearMut to a MaskedLensMut. Under `Skip` and `Propagate` the
+// lanes along `axis` are grouped by the positions of their valid cells, and
+// the GearMut is applied once per group to a copy of the target gathered down
+// to those positions; only the group's own lanes are written back. The copy
+// keeps every lane across the axis with its tags, so a GearMut that checks the
+// target's width or looks up per-column state still sees the target's columns;
+// `axis` should match the lanes the GearMut itself works on. Under `Reject`
+// the GearMut is applied to the whole target once it is known to hold no
+// invalid cell. Groups are written as they complete, so an error from the
+// GearMut leaves earlier groups already transformed.
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Masked<G> {
+    gear: G,
+    axis: Axis,
+}
+
+impl<G> Masked<G> {
+    // Create a new Masked adapter for `gear`, working on lanes along `axis`
+    pub fn new(gear: G, axis: Axis) -> Self {
+        Self { gear, axis }
+    }
+}
+
+impl<V: Clone, G: GearMut<V>> MaskedGearMut<V> for Masked<G> {
+    type Policy = (InvalidCells, G::Policy);
+
+    fn apply_masked(&self, target: &mut MaskedLensMut<'_, V>, policy: &Self::Policy) -> Result<(), MatricalError> {
+        let (invalid_cells, policy) = policy;
+        if *invalid_cells == InvalidCells::Reject {
+            if let Some(index) = target.first_invalid() {
+                return Err(MatricalError::InvalidValue {
+                    operation: "masked",
+                    index: Some(index),
+                });
+            }
+            return self.gear.apply_mut(&mut target.lens, policy);
+        }
+
+        let shape = target.shape();
+        let (lanes, len) = match self.axis {
+            Axis::Column => (shape.cols(), shape.rows()),
+            Axis::Row => (shape.rows(), shape.cols()),
+        };
+        let cell = |lane: usize, position: usize| match self.axis {
+            Axis::Column => Index::new(position, lane),
+            Axis::Row => Index::new(lane, position),
+        };
+
+        // Lanes with the same valid positions share one application of the GearMut
+        let mut groups: BTreeMap<Vec<usize>, Vec<usize>> = BTreeMap::new();
+        for lane in 0..lanes {
+            let mut positions = Vec::with_capacity(len);
+            for position in 0..len {
+                if target.is_valid(cell(lane, position))? {
+                    positions.push(position);
+                }
+            }
+
+            if *invalid_cells == InvalidCells::Propagate && positions.len() < len {
+                for position in 0..len {
+                    target.invalidate(cell(lane, position))?;
+                }
+                continue;
+            }
+            if !positions.is_empty() {
+                groups.entry(positions).or_default().push(lane);
+            }
+        }
+
+        for (positions, group) in groups {
+            let lens = target.lens.as_lens();
+            let gathered = match self.axis {
+                Axis::Column => lens.select_rows(&positions)?,
+                Axis::Row => lens.select_cols(&positions)?,
+            };
+            let mut values = gathered.to_matrix();
+            self.gear.apply_mut(&mut values.as_lens_mut(), policy)?;
+            for lane in group {
+                for (gathered_position, &position) in positions.iter().enumerate() {
+                    let value = values.get(cell(lane, gathered_position))?.clone();
+                    target.lens.set(cell(lane, position), value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn operation(&self) -> OperationId {
+        combined("masked", &[self.gear.operation()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::cog::NoPolicy;
+    use crate::strategies::baseline::{BaselineCog, BaselineStatistic, BaselineWindow, ZScoreAgainstBaseline};
+    use crate::strategies::gear::AddConstant;
+    use crate::strategies::statistics::MeanCenter;
+
+    // Two features over three sessions; feature 1 was not captured in session 1
+    fn captures() -> MaskedMatrix<f64> {
+        MaskedMatrix::from_options((3, 2), vec![Some(1.0), Some(10.0), Some(2.0), None, Some(3.0), Some(30.0)]).unwrap()
+    }
+
+    #[test]
+    fn test_masked_lens_offsets_the_mask() {
+        let matrix = captures();
+        let lens = matrix.lens(Region::new(Index::new(1, 1), Index::new(3, 2)).unwrap()).unwrap();
+        assert!(!lens.is_valid((0, 0)).unwrap());
+        assert_eq!(lens.get((1, 0)).unwrap(), Some(&30.0));
+        assert_eq!(lens.first_invalid(), Some(Index::new(0, 0)));
+        assert_eq!(lens.count_valid(), 1);
+        assert!(lens.is_valid((2, 0)).is_err());
+
+        let copy = lens.to_masked_matrix();
+        assert_eq!(copy.iter().collect::<Vec<_>>(), vec![None, Some(&30.0)]);
+    }
+
+    #[test]
+    fn test_masked_lens_mut_marks_writes_valid() {
+        let mut matrix = captures();
+        let mut lens = matrix.as_lens_mut();
+        lens.set((1, 1), 20.0).unwrap();
+        lens.invalidate((0, 0)).unwrap();
+        assert_eq!(lens.as_masked_lens().first_invalid(), Some(Index::new(0, 0)));
+        assert_eq!(matrix.get((1, 1)).unwrap(), Some(&20.0));
+        assert_eq!(matrix.get((0, 0)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_reject() {
+        let mut matrix = captures();
+        let gear = Masked::new(AddConstant::new(1.0), Axis::Column);
+        assert!(matches!(
+            gear.apply_masked(&mut matrix.as_lens_mut(), &(InvalidCells::Reject, NoPolicy)),
+            Err(MatricalError::InvalidValue { index: Some(index), .. }) if index == Index::new(1, 1)
+        ));
+        assert_eq!(matrix.get((0, 0)).unwrap(), Some(&1.0));
+
+        let region = Region::new(Index::new(0, 0), Index::new(3, 1)).unwrap();
+        gear.apply_masked(&mut matrix.lens_mut(region).unwrap(), &(InvalidCells::default(), NoPolicy))
+            .unwrap();
+        assert_eq!(matrix.get((2, 0)).unwrap(), Some(&4.0));
+    }
+
+    #[test]
+    fn test_skip_uses_only_valid_cells() {
+        let mut matrix = captures();
+        let gear = Masked::new(MeanCenter::new(Axis::Column), Axis::Column);
+        gear.apply_masked(&mut matrix.as_lens_mut(), &(InvalidCells::Skip, NoPolicy)).unwrap();
+
+        // Column 1 is centred on the mean of 10 and 30, not on a zero standing in for the gap
+        assert_eq!(
+            matrix.iter().collect::<Vec<_>>(),
+            vec![Some(&-1.0), Some(&-10.0), Some(&0.0), None, Some(&1.0), Some(&10.0)]
+        );
+        assert_eq!(*matrix.values().get((1, 1)).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_propagate_invalidates_lanes() {
+        let mut matrix = captures();
+        let gear = Masked::new(MeanCenter::new(Axis::Column), Axis::Column);
+        gear.apply_masked(&mut matrix.as_lens_mut(), &(InvalidCells::Propagate, NoPolicy)).unwrap();
+
+        assert_eq!(
+            matrix.iter().collect::<Vec<_>>(),
+            vec![Some(&-1.0), None, Some(&0.0), None, Some(&1.0), None]
+        );
+        assert_eq!(MaskedGearMut::<f64>::operation(&gear).name(), "masked(mean_center@1)");
+    }
+
+    #[test]
+    fn test_skip_keeps_the_columns_of_the_target() {
+        let history = Matrix::from_shape_vec((3, 2), vec![1.0, 10.0, 3.0, 30.0, 5.0, 50.0]).unwrap();
+        let window = BaselineWindow::Rows { start: 0, end: 3 };
+        let baseline = BaselineCog::fit(&history.as_lens(), window, BaselineStatistic::MedianMad).unwrap();
+
+        // The baseline is two columns wide, so each column must reach it at its own index
+        let mut matrix = captures();
+        let gear = Masked::new(ZScoreAgainstBaseline, Axis::Column);
+        gear.apply_masked(&mut matrix.as_lens_mut(), &(InvalidCells::Skip, baseline)).unwrap();
+        assert_eq!(
+            matrix.iter().collect::<Vec<_>>(),
+            vec![Some(&-1.0), Some(&-1.0), Some(&-0.5), None, Some(&0.0), Some(&0.0)]
+        );
+        assert_eq!(*matrix.values().get((1, 1)).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_masked_column_is_skipped() {
+        let mut matrix = MaskedMatrix::all_valid(Matrix::from_fn((2, 2), |row, col| (row + col) as f64).unwrap());
+        matrix.mask_col(1).unwrap();
+        let gear = Masked::new(AddConstant::new(5.0), Axis::Row);
+        gear.apply_masked(&mut matrix.as_lens_mut(), &(InvalidCells::Skip, NoPolicy)).unwrap();
+        assert_eq!(matrix.iter().collect::<Vec<_>>(), vec![Some(&5.0), None, Some(&6.0), None]);
+        assert_eq!(*matrix.values().get((0, 1)).unwrap(), 1.0);
+    }
+}